@@ -0,0 +1,98 @@
+//! The value returned by every `partN`.
+use crate::grid::Grid;
+use std::fmt::{self, Display};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Answer {
+    Int(i128),
+    Text(String),
+    /// Pixels drawn on a screen, to be read by eye.
+    Bitmap(Bitmap),
+}
+
+/// A monochrome image, stored row by row.
+pub type Bitmap = Grid<bool>;
+
+impl Bitmap {
+    /// Whether the pixel at column `x` and row `y` is lit.
+    pub fn is_lit(&self, x: usize, y: usize) -> bool {
+        self.get(x, y) == Some(&true)
+    }
+}
+
+impl Display for Bitmap {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for row in self.rows() {
+            let line: String = row.iter().map(|&p| if p { '█' } else { ' ' }).collect();
+            writeln!(f, "{}", line)?;
+        }
+        Ok(())
+    }
+}
+
+impl Display for Answer {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Answer::Int(n) => write!(f, "{}", n),
+            Answer::Text(s) => write!(f, "{}", s),
+            // start on a new line so the rows are aligned
+            Answer::Bitmap(b) => write!(f, "\n{}", b),
+        }
+    }
+}
+
+macro_rules! from_int {
+    ($($t:ty),*) => {
+        $(
+            impl From<$t> for Answer {
+                fn from(n: $t) -> Self {
+                    Answer::Int(n as i128)
+                }
+            }
+        )*
+    };
+}
+
+from_int!(i32, i64, isize, u32, u64, usize);
+
+impl From<String> for Answer {
+    fn from(s: String) -> Self {
+        Answer::Text(s)
+    }
+}
+
+impl From<&str> for Answer {
+    fn from(s: &str) -> Self {
+        Answer::Text(s.to_string())
+    }
+}
+
+impl From<Bitmap> for Answer {
+    fn from(b: Bitmap) -> Self {
+        Answer::Bitmap(b)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_display() {
+        assert_eq!(Answer::from(54832778815u64).to_string(), "54832778815");
+        assert_eq!(Answer::from(-3isize).to_string(), "-3");
+        assert_eq!(Answer::from("WHTLRMZRC").to_string(), "WHTLRMZRC");
+        let bitmap = Bitmap::new(3, vec![true, false, true, false, true, false]);
+        assert_eq!(Answer::from(bitmap).to_string(), "\n█ █\n █ \n");
+    }
+
+    #[test]
+    fn test_bitmap() {
+        let bitmap = Bitmap::new(2, vec![true, false, false, true, true, true]);
+        assert_eq!((bitmap.width(), bitmap.height()), (2, 3));
+        assert!(bitmap.is_lit(1, 1));
+        assert!(!bitmap.is_lit(1, 0));
+        assert!(!bitmap.is_lit(2, 0));
+        assert_eq!(bitmap.rows().nth(2), Some(&[true, true][..]));
+    }
+}
@@ -0,0 +1,211 @@
+//! Time the parser and the solver of a solution separately, over several runs.
+use crate::registry::Solution;
+use anyhow::{anyhow, Result};
+use itertools::Itertools;
+use std::fmt;
+use std::time::{Duration, Instant};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Stats {
+    pub mean: Duration,
+    pub min: Duration,
+    pub max: Duration,
+}
+
+impl Stats {
+    pub fn new(samples: &[Duration]) -> Self {
+        Stats {
+            mean: samples.iter().sum::<Duration>() / samples.len().max(1) as u32,
+            min: samples.iter().min().copied().unwrap_or_default(),
+            max: samples.iter().max().copied().unwrap_or_default(),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct Timing {
+    pub day: u8,
+    pub part: u8,
+    pub solver: &'static str,
+    pub runs: usize,
+    pub parse: Stats,
+    pub solve: Stats,
+}
+
+/// Parse and solve `input` `runs` times, timing both steps.
+pub fn time(solution: &Solution, input: &str, runs: usize) -> Result<Timing> {
+    if runs == 0 {
+        return Err(anyhow!("need at least one run"));
+    }
+    let mut parse = Vec::with_capacity(runs);
+    let mut solve = Vec::with_capacity(runs);
+    for _ in 0..runs {
+        let start = Instant::now();
+        let parsed = (solution.parse)(input)?;
+        parse.push(start.elapsed());
+
+        let start = Instant::now();
+        (solution.solve)(parsed)?;
+        solve.push(start.elapsed());
+    }
+    Ok(Timing {
+        day: solution.day,
+        part: solution.part,
+        solver: solution.solver,
+        runs,
+        parse: Stats::new(&parse),
+        solve: Stats::new(&solve),
+    })
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    Text,
+    Csv,
+    Json,
+}
+
+impl std::str::FromStr for Format {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "text" => Ok(Format::Text),
+            "csv" => Ok(Format::Csv),
+            "json" => Ok(Format::Json),
+            _ => Err(anyhow!("unknown format '{}', use text, csv or json", s)),
+        }
+    }
+}
+
+/// Durations are written in nanoseconds in CSV and JSON.
+pub struct Report<'a>(pub &'a [Timing], pub Format);
+
+impl fmt::Display for Report<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let Report(timings, format) = self;
+        match format {
+            Format::Text => {
+                writeln!(
+                    f,
+                    "{:>3} {:>4} {:<16} {:>12} {:>12} {:>12} {:>12} {:>12} {:>12}",
+                    "day",
+                    "part",
+                    "solver",
+                    "parse mean",
+                    "parse min",
+                    "parse max",
+                    "solve mean",
+                    "solve min",
+                    "solve max"
+                )?;
+                for t in timings.iter() {
+                    writeln!(
+                        f,
+                        "{:>3} {:>4} {:<16} {:>12} {:>12} {:>12} {:>12} {:>12} {:>12}",
+                        t.day,
+                        t.part,
+                        t.solver,
+                        format!("{:.2?}", t.parse.mean),
+                        format!("{:.2?}", t.parse.min),
+                        format!("{:.2?}", t.parse.max),
+                        format!("{:.2?}", t.solve.mean),
+                        format!("{:.2?}", t.solve.min),
+                        format!("{:.2?}", t.solve.max),
+                    )?;
+                }
+                Ok(())
+            }
+            Format::Csv => {
+                writeln!(f, "day,part,solver,runs,parse_mean_ns,parse_min_ns,parse_max_ns,solve_mean_ns,solve_min_ns,solve_max_ns")?;
+                for t in timings.iter() {
+                    writeln!(
+                        f,
+                        "{},{},{},{},{},{},{},{},{},{}",
+                        t.day,
+                        t.part,
+                        t.solver,
+                        t.runs,
+                        t.parse.mean.as_nanos(),
+                        t.parse.min.as_nanos(),
+                        t.parse.max.as_nanos(),
+                        t.solve.mean.as_nanos(),
+                        t.solve.min.as_nanos(),
+                        t.solve.max.as_nanos(),
+                    )?;
+                }
+                Ok(())
+            }
+            Format::Json => {
+                let stats = |s: &Stats| {
+                    format!(
+                        "{{\"mean_ns\": {}, \"min_ns\": {}, \"max_ns\": {}}}",
+                        s.mean.as_nanos(),
+                        s.min.as_nanos(),
+                        s.max.as_nanos()
+                    )
+                };
+                let entries = timings
+                    .iter()
+                    .map(|t| {
+                        format!(
+                            "  {{\"day\": {}, \"part\": {}, \"solver\": \"{}\", \"runs\": {}, \"parse\": {}, \"solve\": {}}}",
+                            t.day,
+                            t.part,
+                            t.solver,
+                            t.runs,
+                            stats(&t.parse),
+                            stats(&t.solve)
+                        )
+                    })
+                    .join(",\n");
+                writeln!(f, "[\n{}\n]", entries)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::registry;
+
+    #[test]
+    fn test_stats() {
+        let samples = [1, 2, 6].map(Duration::from_millis);
+        assert_eq!(
+            Stats::new(&samples),
+            Stats {
+                mean: Duration::from_millis(3),
+                min: Duration::from_millis(1),
+                max: Duration::from_millis(6),
+            }
+        );
+    }
+
+    #[test]
+    fn test_time() -> Result<()> {
+        let solution = registry::find(1, 2).unwrap();
+        let timing = time(solution, "1\n\n2\n\n3\n\n4", 3)?;
+        assert_eq!((timing.day, timing.part, timing.runs), (1, 2, 3));
+        assert!(timing.parse.min <= timing.parse.mean && timing.parse.mean <= timing.parse.max);
+        assert!(time(solution, "1", 0).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_report() -> Result<()> {
+        let solution = registry::find(6, 1).unwrap();
+        let timings = [time(solution, "bvwbjplbgvbhsrlpgdmjqwftvncz", 2)?];
+        let csv = Report(&timings, Format::Csv).to_string();
+        assert_eq!(csv.lines().count(), 2);
+        assert!(csv
+            .lines()
+            .nth(1)
+            .unwrap()
+            .starts_with("6,1,day06::part1,2,"));
+        let json = Report(&timings, Format::Json).to_string();
+        assert!(json.contains("\"solver\": \"day06::part1\""));
+        Ok(())
+    }
+}
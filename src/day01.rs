@@ -1,33 +1,39 @@
-use anyhow::{anyhow, Result};
+use crate::answer::Answer;
+use crate::error::{Error, Result};
+
+const DAY: u8 = 1;
 
 pub fn parse_input(input: &str) -> Result<Vec<Vec<u32>>> {
-    input
-        .split("\n\n")
-        .map(|block| {
-            block
-                .lines()
-                .map(|line| {
-                    line.parse()
-                        .map_err(|e| anyhow!("Failed to convert '{}': {:?}", line, e))
-                })
-                .collect()
-        })
-        .collect()
+    let mut blocks = vec![];
+    let mut block = vec![];
+    for (i, line) in input.lines().enumerate() {
+        if line.is_empty() {
+            blocks.push(std::mem::take(&mut block));
+        } else {
+            block.push(
+                line.parse()
+                    .map_err(|e| Error::parse(DAY, i + 1, line, e))?,
+            );
+        }
+    }
+    blocks.push(block);
+    Ok(blocks)
 }
 
-pub fn part1(input: Vec<Vec<u32>>) -> u32 {
+pub fn part1(input: Vec<Vec<u32>>) -> Result<Answer> {
     input
         .iter()
         .map(|block| block.iter().sum::<u32>())
         .max()
-        .unwrap()
+        .map(Answer::from)
+        .ok_or_else(|| Error::no_solution(DAY, "no elves"))
 }
 
-pub fn part2(input: Vec<Vec<u32>>) -> u32 {
+pub fn part2(input: Vec<Vec<u32>>) -> Result<Answer> {
     let mut sums: Vec<_> = input
         .iter()
         .map(|block| block.iter().sum::<u32>())
         .collect();
     sums.sort_unstable();
-    sums.iter().rev().take(3).sum()
+    Ok(sums.iter().rev().take(3).sum::<u32>().into())
 }
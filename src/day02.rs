@@ -1,4 +1,7 @@
-use anyhow::{Context, Result};
+use crate::answer::Answer;
+use crate::error::{parse_lines, Error, Result};
+
+const DAY: u8 = 2;
 
 #[derive(Debug, PartialEq, Clone)]
 enum Hand {
@@ -24,49 +27,46 @@ fn score(me: &Hand, opponent: &Hand) -> u32 {
 }
 
 pub fn parse_input(input: &str) -> Result<Vec<(String, String)>> {
-    input
-        .lines()
-        .map(|line| {
-            let (a, b) = line.split_once(' ').context("wrong input")?;
-            Ok((a.to_owned(), b.to_owned()))
-        })
-        .collect()
+    parse_lines(DAY, input, |line| match line.split_once(' ') {
+        Some((a @ ("A" | "B" | "C"), b @ ("X" | "Y" | "Z"))) => Ok((a.to_owned(), b.to_owned())),
+        _ => Err("expected 'A|B|C X|Y|Z'"),
+    })
 }
 
-fn strat1(h: &str) -> Hand {
+fn opponent(h: &str) -> Result<Hand> {
     match h {
-        "X" => Hand::Rock,
-        "Y" => Hand::Paper,
-        "Z" => Hand::Scissors,
-        _ => unreachable!(),
+        "A" => Ok(Hand::Rock),
+        "B" => Ok(Hand::Paper),
+        "C" => Ok(Hand::Scissors),
+        _ => Err(Error::input(DAY, format!("unknown hand {}", h))),
     }
 }
-pub fn part1(input: Vec<(String, String)>) -> u32 {
+
+fn strat1(h: &str) -> Result<Hand> {
+    match h {
+        "X" => Ok(Hand::Rock),
+        "Y" => Ok(Hand::Paper),
+        "Z" => Ok(Hand::Scissors),
+        _ => Err(Error::input(DAY, format!("unknown hand {}", h))),
+    }
+}
+pub fn part1(input: Vec<(String, String)>) -> Result<Answer> {
     input
         .into_iter()
         .map(|(h1, h2)| {
-            let me = strat1(h2.as_str());
-            let opponent = match h1.as_str() {
-                "A" => Hand::Rock,
-                "B" => Hand::Paper,
-                "C" => Hand::Scissors,
-                _ => unreachable!("wrong input"),
-            };
-            score(&me, &opponent)
+            let me = strat1(h2.as_str())?;
+            let opponent = opponent(h1.as_str())?;
+            Ok(score(&me, &opponent))
         })
-        .sum()
+        .sum::<Result<u32>>()
+        .map(Answer::from)
 }
 
-pub fn part2(input: Vec<(String, String)>) -> u32 {
+pub fn part2(input: Vec<(String, String)>) -> Result<Answer> {
     input
         .into_iter()
         .map(|(h1, h2)| {
-            let opponent = match h1.as_str() {
-                "A" => Hand::Rock,
-                "B" => Hand::Paper,
-                "C" => Hand::Scissors,
-                _ => unreachable!("wrong input"),
-            };
+            let opponent = opponent(h1.as_str())?;
             let me = match (&opponent, h2.as_str()) {
                 // X lose, Y draw, Z win
                 (Hand::Rock, "X") => Hand::Scissors,
@@ -78,9 +78,10 @@ pub fn part2(input: Vec<(String, String)>) -> u32 {
                 (Hand::Scissors, "X") => Hand::Paper,
                 (Hand::Scissors, "Y") => Hand::Scissors,
                 (Hand::Scissors, "Z") => Hand::Rock,
-                (_, _) => unreachable!("wrong input: {:?} {:?}", &opponent, &h1),
+                (_, h) => return Err(Error::input(DAY, format!("unknown outcome {}", h))),
             };
-            score(&me, &opponent)
+            Ok(score(&me, &opponent))
         })
-        .sum()
+        .sum::<Result<u32>>()
+        .map(Answer::from)
 }
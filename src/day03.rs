@@ -1,67 +1,83 @@
+use crate::answer::Answer;
+use crate::error::{parse_lines, Error, Result};
 use itertools::Itertools;
 use std::collections::HashSet;
 
-pub fn parse_input(input: &str) -> Vec<(String, String)> {
+const DAY: u8 = 3;
+
+pub fn parse_input(input: &str) -> Result<Vec<(String, String)>> {
     //  let input = "vJrwpWtwJgWrhcsFMMfFFhFp
     //jqHRNqRjqzjGDLGLrsFMfFZSrLrFZsSL
     //PmmdzqPrVvPwwTWBwg
     //wMqvLMZHhHMvwLHjbvcjnnSBnvTQFn
     //ttgJtRGJQctTZtZT
     //CrZsJsPPZsGzwwsLwLmpwMDw";
-    input
-        .lines()
-        .map(|line| {
-            let half = line.len() / 2; // OK because ASCII
-            (line[..half].to_string(), line[half..].to_string())
-        })
-        .collect()
+    parse_lines(DAY, input, |line| {
+        if !line.chars().all(|c| c.is_ascii_alphabetic()) {
+            return Err("items must be letters");
+        }
+        if line.len() % 2 != 0 {
+            return Err("odd number of items");
+        }
+        let half = line.len() / 2; // OK because ASCII
+        Ok((line[..half].to_string(), line[half..].to_string()))
+    })
 }
 
-pub fn part1(input: Vec<(String, String)>) -> u32 {
+fn priority(c: char) -> Result<u32> {
+    match c {
+        'a'..='z' => Ok((c as u32 - 'a' as u32) + 1),
+        'A'..='Z' => Ok((c as u32 - 'A' as u32) + 26 + 1),
+        _ => Err(Error::input(DAY, format!("unknown item {}", c))),
+    }
+}
+
+pub fn part1(input: Vec<(String, String)>) -> Result<Answer> {
     input
         .into_iter()
         .map(|(l, r)| {
             let set: HashSet<char> = l.chars().collect();
-            for c in r.chars() {
-                if set.contains(&c) {
-                    return c;
-                }
-            }
-            unreachable!("no common char");
+            let c = r
+                .chars()
+                .find(|c| set.contains(c))
+                .ok_or_else(|| Error::no_solution(DAY, format!("no common item in {}{}", l, r)))?;
+            priority(c)
         })
-        .map(|c| match c {
-            'a'..='z' => (c as u32 - 'a' as u32) + 1,
-            'A'..='Z' => (c as u32 - 'A' as u32) + 26 + 1,
-            _ => unreachable!("wrong input"),
-        })
-        .sum()
+        .sum::<Result<u32>>()
+        .map(Answer::from)
 }
 
-pub fn parse_input_p2(input: &str) -> Vec<char> {
+pub fn parse_input_p2(input: &str) -> Result<Vec<char>> {
     input
         .lines()
         .chunks(3)
         .into_iter()
-        .map(|mut chunk| {
-            let s: HashSet<char> = chunk.next().unwrap().chars().collect();
-            let s2: HashSet<char> = chunk.next().unwrap().chars().collect();
-            for c in chunk.next().unwrap().chars() {
-                if (s.intersection(&s2)).contains(&c) {
-                    return c;
+        .enumerate()
+        .map(|(i, chunk)| {
+            let line = 3 * i + 1;
+            match chunk.collect_tuple() {
+                Some((a, b, c)) => {
+                    let s: HashSet<char> = a.chars().collect();
+                    let s2: HashSet<char> = b.chars().collect();
+                    c.chars()
+                        .find(|item| s.contains(item) && s2.contains(item))
+                        .ok_or_else(|| Error::parse(DAY, line, a, "no item common to the group"))
                 }
+                None => Err(Error::parse(
+                    DAY,
+                    line,
+                    "",
+                    "group of less than 3 rucksacks",
+                )),
             }
-            unreachable!("no common char");
         })
         .collect()
 }
 
-pub fn part2(input: Vec<char>) -> u32 {
+pub fn part2(input: Vec<char>) -> Result<Answer> {
     input
         .into_iter()
-        .map(|c| match c {
-            'a'..='z' => (c as u32 - 'a' as u32) + 1,
-            'A'..='Z' => (c as u32 - 'A' as u32) + 26 + 1,
-            _ => unreachable!("wrong input"),
-        })
-        .sum()
+        .map(priority)
+        .sum::<Result<u32>>()
+        .map(Answer::from)
 }
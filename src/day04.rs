@@ -1,4 +1,8 @@
-use anyhow::{Context, Result};
+use crate::answer::Answer;
+use crate::error::{parse_lines, Result};
+use anyhow::Context;
+
+const DAY: u8 = 4;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct Range {
@@ -13,7 +17,7 @@ impl Range {
             end: t.1,
         }
     }
-    fn from_str(s: &str) -> Result<Self> {
+    fn from_str(s: &str) -> anyhow::Result<Self> {
         let (t1, t2) = s.split_once('-').context("no dash")?;
         Ok(Self::from_tuple((t1.parse()?, t2.parse()?)))
     }
@@ -34,22 +38,24 @@ pub fn parse_input(input: &str) -> Result<Vec<Pair>> {
     // 2-8,3-7
     // 6-6,4-6
     // 2-6,4-8";
-    input
-        .lines()
-        .map(|l| {
-            let (t1, t2) = l.split_once(',').context("no separator")?;
-            Ok((Range::from_str(t1)?, Range::from_str(t2)?))
-        })
-        .collect()
+    parse_lines(DAY, input, |l| {
+        let (t1, t2) = l.split_once(',').context("no separator")?;
+        anyhow::Ok((Range::from_str(t1)?, Range::from_str(t2)?))
+    })
 }
 
-pub fn part1(input: Vec<Pair>) -> usize {
-    input
+pub fn part1(input: Vec<Pair>) -> Result<Answer> {
+    Ok(input
         .iter()
         .filter(|(r1, r2)| r1.included(r2) || r2.included(r1))
         .count()
+        .into())
 }
 
-pub fn part2(input: Vec<Pair>) -> usize {
-    input.iter().filter(|(r1, r2)| r1.overlap(r2)).count()
+pub fn part2(input: Vec<Pair>) -> Result<Answer> {
+    Ok(input
+        .iter()
+        .filter(|(r1, r2)| r1.overlap(r2))
+        .count()
+        .into())
 }
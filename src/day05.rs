@@ -1,23 +1,41 @@
-use anyhow::{Context, Error, Result};
+use crate::answer::Answer;
+use crate::error::{Error, Result};
+use anyhow::{bail, Context};
+use itertools::Itertools;
 use regex::Regex;
-use std::collections::HashMap;
+use std::collections::BTreeMap;
+use std::fmt;
+use std::ops::Range;
 use std::str::FromStr;
 
-const NUM_CRATES: u32 = 9;
+const DAY: u8 = 5;
 
-type Crates = HashMap<u32, Vec<char>>;
+/// A crate is named by whatever is between its brackets, usually one letter.
+pub type Crate = String;
+/// The stacks by label, each from bottom to top.
+pub type Crates = BTreeMap<u32, Vec<Crate>>;
 
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct Move {
-    quantity: u32,
-    origin: u32,
-    destination: u32,
+    pub quantity: u32,
+    pub origin: u32,
+    pub destination: u32,
+}
+
+impl fmt::Display for Move {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "move {} from {} to {}",
+            self.quantity, self.origin, self.destination
+        )
+    }
 }
 
 impl FromStr for Move {
-    type Err = Error;
+    type Err = anyhow::Error;
 
-    fn from_str(movement: &str) -> Result<Self> {
+    fn from_str(movement: &str) -> anyhow::Result<Self> {
         let re = Regex::new(r"^move (\d+) from (\d+) to (\d+)$")?;
         let caps = re.captures(movement).context("error in regex")?;
 
@@ -32,107 +50,577 @@ impl FromStr for Move {
     }
 }
 
-fn parse_moves(moves: &str) -> Result<Vec<Move>> {
-    moves.lines().map(|m| m.parse()).collect()
+fn parse_moves(moves: &str, first_line: usize) -> Result<Vec<Move>> {
+    moves
+        .lines()
+        .enumerate()
+        .map(|(i, m)| {
+            m.parse()
+                .map_err(|e| Error::parse(DAY, first_line + i, m, e))
+        })
+        .collect()
 }
 
-fn parse_crates(crates: &str) -> Result<Crates> {
-    let re = Regex::new(
-        r"^(?:\[([A-Z])\]|   ) (?:\[([A-Z])\]|   ) (?:\[([A-Z])\]|   ) (?:\[([A-Z])\]|   ) (?:\[([A-Z])\]|   ) (?:\[([A-Z])\]|   ) (?:\[([A-Z])\]|   ) (?:\[([A-Z])\]|   ) (?:\[([A-Z])\]|   )$",
-    )?;
-    let mut parsed: Crates = HashMap::new();
-    for line in crates.lines() {
-        let caps = match re.captures(line) {
-            Some(m) => m,
-            None => continue,
+/// Each whitespace separated word of `line`, with its span.
+fn words(line: &str) -> Vec<(Range<usize>, &str)> {
+    let mut words = vec![];
+    let mut start = None;
+    for (i, c) in line.char_indices().chain([(line.len(), ' ')]) {
+        match (c.is_whitespace(), start) {
+            (true, Some(s)) => {
+                words.push((s..i, &line[s..i]));
+                start = None;
+            }
+            (false, None) => start = Some(i),
+            _ => (),
+        }
+    }
+    words
+}
+
+/// The crates drawn on one row, with the label of their stack. A crate belongs
+/// to the stack whose label is below it.
+fn parse_row(line: &str, labels: &[(u32, Range<usize>)]) -> anyhow::Result<Vec<(u32, Crate)>> {
+    let mut row: Vec<(u32, Crate)> = vec![];
+    for (span, word) in words(line) {
+        let name = word
+            .strip_prefix('[')
+            .and_then(|w| w.strip_suffix(']'))
+            .filter(|name| !name.is_empty())
+            .with_context(|| format!("expected a crate like [A], got '{}'", word))?;
+        let mut below = labels
+            .iter()
+            .filter(|(_, l)| l.start < span.end && span.start < l.end);
+        let label = match (below.next(), below.next()) {
+            (Some((label, _)), None) => *label,
+            _ => bail!("crate {} is not above exactly one stack", word),
         };
-        for i in 1..=NUM_CRATES {
-            match caps.get(i.try_into()?) {
-                Some(s) => {
-                    let v = parsed.entry(i).or_default();
-                    v.push(s.as_str().chars().next().context("no char")?);
-                }
-                None => continue,
+        if row.iter().any(|(l, _)| *l == label) {
+            bail!("two crates above stack {}", label);
+        }
+        row.push((label, name.to_string()));
+    }
+    Ok(row)
+}
+
+/// Parse the drawing of the stacks. The last line labels the stacks, and sets
+/// their number and the columns they are drawn in.
+fn parse_crates(drawing: &str) -> Result<Crates> {
+    let lines: Vec<&str> = drawing.lines().collect();
+    let (label_line, rows) = lines
+        .split_last()
+        .ok_or_else(|| Error::input(DAY, "no stacks"))?;
+    let mut labels = vec![];
+    for (span, word) in words(label_line) {
+        let label: u32 = word
+            .parse()
+            .map_err(|e| Error::parse(DAY, lines.len(), label_line, e))?;
+        if labels.iter().any(|(l, _)| *l == label) {
+            let reason = format!("stack {} appears twice", label);
+            return Err(Error::parse(DAY, lines.len(), label_line, reason));
+        }
+        labels.push((label, span));
+    }
+    if labels.is_empty() {
+        return Err(Error::parse(
+            DAY,
+            lines.len(),
+            label_line,
+            "no stack labels",
+        ));
+    }
+
+    let mut crates: Crates = labels.iter().map(|(l, _)| (*l, vec![])).collect();
+    // bottom up, so that each stack is in the order crates are piled
+    for (height, (i, line)) in rows.iter().enumerate().rev().enumerate() {
+        let row = parse_row(line, &labels).map_err(|e| Error::parse(DAY, i + 1, line, e))?;
+        for (label, name) in row {
+            let stack = crates.entry(label).or_default();
+            if stack.len() != height {
+                let reason = format!("crate [{}] floats above stack {}", name, label);
+                return Err(Error::parse(DAY, i + 1, line, reason));
             }
+            stack.push(name);
         }
     }
-    for i in 1..=NUM_CRATES {
-        parsed.get_mut(&i).context("no entry")?.reverse()
+    Ok(crates)
+}
+
+/// Draw stacks the way the puzzle does, one column per stack with the labels
+/// on the last line. All columns are as wide as the widest crate or label, so
+/// that `parse_input` reads the drawing back as the same stacks.
+pub struct Drawing<'a>(pub &'a Crates);
+
+impl fmt::Display for Drawing<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let Drawing(crates) = self;
+        let labels: Vec<String> = crates.keys().map(|l| l.to_string()).collect();
+        let width = crates
+            .values()
+            .flatten()
+            .map(|c| c.len() + 2)
+            .chain(labels.iter().map(|l| l.len()))
+            .max()
+            .unwrap_or(0);
+        let centered = |s: &str| {
+            let left = (width - s.len()) / 2;
+            format!(
+                "{:left$}{}{:right$}",
+                "",
+                s,
+                "",
+                right = width - s.len() - left
+            )
+        };
+        let height = crates.values().map(|s| s.len()).max().unwrap_or(0);
+        for row in (0..height).rev() {
+            let line = crates
+                .values()
+                .map(|stack| match stack.get(row) {
+                    Some(c) => centered(&format!("[{}]", c)),
+                    None => centered(""),
+                })
+                .join(" ");
+            writeln!(f, "{}", line)?;
+        }
+        write!(f, "{}", labels.iter().map(|l| centered(l)).join(" "))
     }
-    Ok(parsed)
 }
 
 pub fn parse_input(input: &str) -> Result<(Crates, Vec<Move>)> {
-    let _input = "    [D]    
-[N] [C]    
-[Z] [M] [P]
- 1   2   3 
-
-move 1 from 2 to 1
-move 3 from 1 to 3
-move 2 from 2 to 1
-move 1 from 1 to 2
-";
-    let (crates, moves) = input.split_once("\n\n").context("wrong input")?;
-    let moves = parse_moves(moves)?;
+    let (crates, moves) = input
+        .split_once("\n\n")
+        .ok_or_else(|| Error::input(DAY, "no empty line between crates and moves"))?;
+    let moves = parse_moves(moves, crates.lines().count() + 2)?;
     let crates = parse_crates(crates)?;
     Ok((crates, moves))
 }
 
-pub fn fallible_part1(mut crates: Crates, moves: Vec<Move>) -> Result<String> {
-    for m in moves {
-        for _ in 0..m.quantity {
-            let v = crates
-                .get_mut(&m.origin)
-                .context("no entry")?
-                .pop()
-                .context("empty")?;
-            crates.get_mut(&m.destination).context("no entry")?.push(v);
+/// Why a move cannot be done.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MoveError {
+    UnknownStack(u32),
+    NotEnoughCrates { stack: u32, has: usize, wanted: u32 },
+}
+
+impl fmt::Display for MoveError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            MoveError::UnknownStack(stack) => write!(f, "there is no stack {}", stack),
+            MoveError::NotEnoughCrates { stack, has: 0, .. } => {
+                write!(f, "stack {} is empty", stack)
+            }
+            MoveError::NotEnoughCrates { stack, has, wanted } => write!(
+                f,
+                "stack {} has {} crate(s), cannot take {}",
+                stack, has, wanted
+            ),
         }
     }
-    let mut r = Vec::new();
-    for i in 1..=NUM_CRATES {
-        r.push(
-            crates
-                .get_mut(&i)
-                .context("no entry")?
-                .pop()
-                .context("empty")?,
-        )
+}
+
+impl std::error::Error for MoveError {}
+
+/// The stacks, rearranged one move at a time. The state after every move
+/// can be kept, from the starting one to the current one.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CrateYard {
+    stacks: Crates,
+    history: Option<Vec<Crates>>,
+}
+
+impl CrateYard {
+    /// A yard that keeps only the current state.
+    pub fn new(crates: Crates) -> Self {
+        CrateYard {
+            stacks: crates,
+            history: None,
+        }
+    }
+
+    /// A yard that records the state after every move.
+    pub fn with_history(crates: Crates) -> Self {
+        CrateYard {
+            history: Some(vec![crates.clone()]),
+            stacks: crates,
+        }
+    }
+
+    pub fn stacks(&self) -> &Crates {
+        &self.stacks
+    }
+
+    /// Every state so far, the first one being the starting state and then
+    /// one per move, if the yard was created [`CrateYard::with_history`].
+    pub fn history(&self) -> Option<&[Crates]> {
+        self.history.as_deref()
+    }
+
+    /// The crate on top of each stack, `None` for empty stacks.
+    pub fn tops(&self) -> Vec<(u32, Option<&Crate>)> {
+        self.stacks
+            .iter()
+            .map(|(label, stack)| (*label, stack.last()))
+            .collect()
+    }
+
+    /// Check that `m` can be done, leaving the stacks untouched.
+    pub fn check(&self, m: &Move) -> std::result::Result<(), MoveError> {
+        if !self.stacks.contains_key(&m.destination) {
+            return Err(MoveError::UnknownStack(m.destination));
+        }
+        let has = self
+            .stacks
+            .get(&m.origin)
+            .ok_or(MoveError::UnknownStack(m.origin))?
+            .len();
+        if has < m.quantity as usize {
+            return Err(MoveError::NotEnoughCrates {
+                stack: m.origin,
+                has,
+                wanted: m.quantity,
+            });
+        }
+        Ok(())
+    }
+
+    /// Lift the crates of `m` all at once, keeping their order. This is one
+    /// step of a move for a [`Crane`], it is not recorded in the history.
+    pub fn lift(&mut self, m: &Move) -> std::result::Result<(), MoveError> {
+        self.check(m)?;
+        if m.origin != m.destination {
+            // both stacks exist, it was checked above
+            if let Some(origin) = self.stacks.get_mut(&m.origin) {
+                let lifted = origin.split_off(origin.len() - m.quantity as usize);
+                self.stacks.entry(m.destination).or_default().extend(lifted);
+            }
+        }
+        Ok(())
+    }
+
+    /// Do `m` with `crane` and record the new state. Returns the number of
+    /// lifts it took. If `m` cannot be done, the yard is left untouched.
+    pub fn perform(
+        &mut self,
+        m: &Move,
+        crane: &mut dyn Crane,
+    ) -> std::result::Result<usize, MoveError> {
+        self.check(m)?;
+        let lifts = crane.apply(self, m)?;
+        if let Some(history) = &mut self.history {
+            history.push(self.stacks.clone());
+        }
+        Ok(lifts)
+    }
+}
+
+/// How a crane carries out a move, as one or several lifts.
+pub trait Crane {
+    /// Do `m` on `yard` with [`CrateYard::lift`], and return how many lifts
+    /// it took. If `m` cannot be done, `yard` is left untouched.
+    fn apply(&mut self, yard: &mut CrateYard, m: &Move) -> std::result::Result<usize, MoveError>;
+}
+
+/// Moves crates one at a time, so they land in reverse order.
+pub struct CrateMover9000;
+
+/// Moves all the crates at once.
+pub struct CrateMover9001;
+
+/// Lifts at most `capacity` crates at once, so bigger moves are done in
+/// several lifts, from the top of the stack down.
+pub struct LimitedCrane {
+    capacity: u32,
+}
+
+/// Counts the lifts and crates moved by another crane.
+pub struct CountingCrane<C> {
+    pub crane: C,
+    pub lifts: usize,
+    pub crates: usize,
+}
+
+impl Crane for CrateMover9000 {
+    fn apply(&mut self, yard: &mut CrateYard, m: &Move) -> std::result::Result<usize, MoveError> {
+        LimitedCrane::new(1).apply(yard, m)
+    }
+}
+
+impl Crane for CrateMover9001 {
+    fn apply(&mut self, yard: &mut CrateYard, m: &Move) -> std::result::Result<usize, MoveError> {
+        yard.lift(m)?;
+        Ok(1)
+    }
+}
+
+impl LimitedCrane {
+    /// Panics if `capacity` is 0.
+    pub fn new(capacity: u32) -> Self {
+        assert!(capacity > 0, "a crane must lift at least one crate");
+        LimitedCrane { capacity }
+    }
+}
+
+impl Crane for LimitedCrane {
+    fn apply(&mut self, yard: &mut CrateYard, m: &Move) -> std::result::Result<usize, MoveError> {
+        yard.check(m)?;
+        let mut left = m.quantity;
+        let mut lifts = 0;
+        while left > 0 {
+            let quantity = left.min(self.capacity);
+            yard.lift(&Move { quantity, ..*m })?;
+            left -= quantity;
+            lifts += 1;
+        }
+        Ok(lifts)
     }
-    Ok(r.into_iter().collect())
 }
 
-pub fn part1((crates, moves): (Crates, Vec<Move>)) -> String {
-    match fallible_part1(crates, moves) {
-        Ok(s) => s,
-        Err(_) => unreachable!("wrong processing"),
+impl<C: Crane> CountingCrane<C> {
+    pub fn new(crane: C) -> Self {
+        CountingCrane {
+            crane,
+            lifts: 0,
+            crates: 0,
+        }
+    }
+
+    /// The cost of the moves so far, given the cost of a lift and of moving
+    /// one crate.
+    pub fn cost(&self, per_lift: u64, per_crate: u64) -> u64 {
+        self.lifts as u64 * per_lift + self.crates as u64 * per_crate
+    }
+}
+
+impl<C: Crane> Crane for CountingCrane<C> {
+    fn apply(&mut self, yard: &mut CrateYard, m: &Move) -> std::result::Result<usize, MoveError> {
+        let lifts = self.crane.apply(yard, m)?;
+        self.lifts += lifts;
+        self.crates += m.quantity as usize;
+        Ok(lifts)
     }
 }
 
-fn fallible_part2(mut crates: Crates, moves: Vec<Move>) -> Result<String> {
+/// Do all `moves` on `yard` with `crane`.
+pub fn rearrange(mut yard: CrateYard, moves: &[Move], crane: &mut dyn Crane) -> Result<CrateYard> {
     for m in moves {
-        let len = crates[&m.origin].len();
-        let idx: usize = len - m.quantity as usize;
-        let stack = crates
-            .get_mut(&m.origin)
-            .context("no entry")?
-            .split_off(idx);
-        crates
-            .get_mut(&m.destination)
-            .context("no entry")?
-            .extend(stack.clone());
-    }
-    let mut r = Vec::new();
-    for i in 1..=NUM_CRATES {
-        r.push(crates[&i].iter().last().context("empty")?)
-    }
-    Ok(r.into_iter().collect())
-}
-
-pub fn part2((crates, moves): (Crates, Vec<Move>)) -> String {
-    match fallible_part2(crates, moves) {
-        Ok(s) => s,
-        Err(_) => unreachable!("wrong processing"),
+        yard.perform(m, crane)
+            .map_err(|e| Error::no_solution(DAY, format!("cannot {}: {}", m, e)))?;
+    }
+    Ok(yard)
+}
+
+fn top_crates(yard: &CrateYard) -> Result<Answer> {
+    yard.tops()
+        .into_iter()
+        .map(|(label, top)| {
+            top.map(String::as_str)
+                .ok_or_else(|| Error::no_solution(DAY, format!("stack {} ends up empty", label)))
+        })
+        .collect::<Result<String>>()
+        .map(Answer::from)
+}
+
+pub fn part1((crates, moves): (Crates, Vec<Move>)) -> Result<Answer> {
+    top_crates(&rearrange(
+        CrateYard::new(crates),
+        &moves,
+        &mut CrateMover9000,
+    )?)
+}
+
+pub fn part2((crates, moves): (Crates, Vec<Move>)) -> Result<Answer> {
+    top_crates(&rearrange(
+        CrateYard::new(crates),
+        &moves,
+        &mut CrateMover9001,
+    )?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use indoc::indoc;
+
+    const EXAMPLE: &str = indoc! {"
+            [D]    
+        [N] [C]    
+        [Z] [M] [P]
+         1   2   3 
+
+        move 1 from 2 to 1
+        move 3 from 1 to 3
+        move 2 from 2 to 1
+        move 1 from 1 to 2
+    "};
+
+    fn stacks(stacks: &[(u32, &[&str])]) -> Crates {
+        stacks
+            .iter()
+            .map(|(l, s)| (*l, s.iter().map(|c| c.to_string()).collect()))
+            .collect()
+    }
+
+    #[test]
+    fn test_parse_crates() -> Result<()> {
+        let (crates, moves) = parse_input(EXAMPLE)?;
+        assert_eq!(
+            crates,
+            stacks(&[(1, &["Z", "N"]), (2, &["M", "C", "D"]), (3, &["P"])])
+        );
+        assert_eq!(moves.len(), 4);
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_wide_crates() -> Result<()> {
+        let drawing = indoc! {"
+            [AB]                     [KL]
+            [CD]           [GHI]     [MN]
+             1    2    3    10   11   12 "};
+        assert_eq!(
+            parse_crates(drawing)?,
+            stacks(&[
+                (1, &["CD", "AB"]),
+                (2, &[]),
+                (3, &[]),
+                (10, &["GHI"]),
+                (11, &[]),
+                (12, &["MN", "KL"]),
+            ])
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_malformed() {
+        let err = |line, text: &str, reason: &str| Err(Error::parse(DAY, line, text, reason));
+        assert_eq!(
+            parse_crates("[A] B\n 1  2"),
+            err(1, "[A] B", "expected a crate like [A], got 'B'")
+        );
+        assert_eq!(
+            parse_crates("[A]\n    [B]\n 1   2"),
+            err(1, "[A]", "crate [A] floats above stack 1")
+        );
+        assert_eq!(
+            parse_crates("        [C]\n 1   2"),
+            err(1, "        [C]", "crate [C] is not above exactly one stack")
+        );
+        assert_eq!(
+            parse_crates("[A]\n 1   x"),
+            err(2, " 1   x", "invalid digit found in string")
+        );
+    }
+
+    #[test]
+    fn test_drawing() -> Result<()> {
+        let (drawing, _) = EXAMPLE.split_once("\n\n").unwrap();
+        let (crates, moves) = parse_input(EXAMPLE)?;
+        assert_eq!(Drawing(&crates).to_string(), drawing);
+        assert_eq!(parse_crates(drawing)?, crates);
+
+        let yard = rearrange(CrateYard::new(crates), &moves[..2], &mut CrateMover9000)?;
+        let drawing = "        [Z]\n        [N]\n    [C] [D]\n    [M] [P]\n 1   2   3 ";
+        assert_eq!(Drawing(yard.stacks()).to_string(), drawing);
+        assert_eq!(&parse_crates(drawing)?, yard.stacks());
+        Ok(())
+    }
+
+    #[test]
+    fn test_drawing_wide_crates() -> Result<()> {
+        let crates = stacks(&[(1, &["AB"]), (2, &[]), (10, &["C", "DEF"])]);
+        let drawing = Drawing(&crates).to_string();
+        assert_eq!(
+            drawing,
+            "            [DEF]\n[AB]         [C] \n  1     2    10  "
+        );
+        assert_eq!(parse_crates(&drawing)?, crates);
+        Ok(())
+    }
+
+    #[test]
+    fn test_crate_yard() -> Result<()> {
+        let (crates, moves) = parse_input(EXAMPLE)?;
+        let mut yard = CrateYard::with_history(crates.clone());
+        assert_eq!(yard.perform(&moves[0], &mut CrateMover9000), Ok(1));
+        assert_eq!(yard.perform(&moves[1], &mut CrateMover9000), Ok(3));
+        assert_eq!(
+            yard.stacks(),
+            &stacks(&[(1, &[]), (2, &["M", "C"]), (3, &["P", "D", "N", "Z"])])
+        );
+        // one state per move
+        let history = yard.history().unwrap();
+        assert_eq!(history.len(), 3);
+        assert_eq!(history[0], crates);
+        assert_eq!(
+            history[1],
+            stacks(&[(1, &["Z", "N", "D"]), (2, &["M", "C"]), (3, &["P"])])
+        );
+        assert_eq!(yard.tops()[0], (1, None));
+
+        let take = |quantity, origin, destination| Move {
+            quantity,
+            origin,
+            destination,
+        };
+        assert_eq!(
+            yard.perform(&take(1, 1, 2), &mut CrateMover9000),
+            Err(MoveError::NotEnoughCrates {
+                stack: 1,
+                has: 0,
+                wanted: 1
+            })
+        );
+        assert_eq!(
+            yard.perform(&take(1, 2, 4), &mut CrateMover9001),
+            Err(MoveError::UnknownStack(4))
+        );
+        // a failed move leaves no trace
+        assert_eq!(yard.history().map(<[_]>::len), Some(3));
+        assert_eq!(CrateYard::new(crates).history(), None);
+        Ok(())
+    }
+
+    #[test]
+    fn test_cranes() -> Result<()> {
+        let (crates, moves) = parse_input(EXAMPLE)?;
+        let mut crane = CountingCrane::new(LimitedCrane::new(2));
+        let yard = rearrange(CrateYard::new(crates.clone()), &moves, &mut crane)?;
+        // the 3 crates of the second move are lifted as [N] [D] then [Z]
+        assert_eq!(top_crates(&yard)?, "MCZ".into());
+        assert_eq!((crane.lifts, crane.crates), (5, 7));
+        assert_eq!(crane.cost(10, 1), 57);
+
+        let mut crane = CountingCrane::new(CrateMover9000);
+        let yard = rearrange(CrateYard::with_history(crates.clone()), &moves, &mut crane)?;
+        assert_eq!(crane.lifts, 7);
+        assert_eq!(yard.history().map(<[_]>::len), Some(5));
+
+        let mut crane = CountingCrane::new(CrateMover9001);
+        rearrange(CrateYard::new(crates), &moves, &mut crane)?;
+        assert_eq!(crane.lifts, 4);
+        Ok(())
+    }
+
+    #[test]
+    fn test_part_errors() -> Result<()> {
+        let (crates, _) = parse_input(EXAMPLE)?;
+        let moves = vec!["move 4 from 1 to 2".parse().unwrap()];
+        assert_eq!(
+            part1((crates, moves)),
+            Err(Error::no_solution(
+                DAY,
+                "cannot move 4 from 1 to 2: stack 1 has 2 crate(s), cannot take 4"
+            ))
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_parts() -> Result<()> {
+        assert_eq!(parse_input(EXAMPLE).and_then(part1)?, "CMZ".into());
+        assert_eq!(parse_input(EXAMPLE).and_then(part2)?, "MCD".into());
+        Ok(())
     }
 }
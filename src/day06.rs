@@ -1,8 +1,11 @@
-use anyhow::{Context, Result};
+use crate::answer::Answer;
+use crate::error::{Error, Result};
 use std::collections::HashSet;
 
-pub fn parse_input(input: &str) -> String {
-    input.to_owned()
+const DAY: u8 = 6;
+
+pub fn parse_input(input: &str) -> Result<String> {
+    Ok(input.to_owned())
 }
 
 fn find_first_different(input: String, n: usize) -> Result<usize> {
@@ -11,21 +14,15 @@ fn find_first_different(input: String, n: usize) -> Result<usize> {
         .windows(n)
         .enumerate()
         .find(|&(_, p)| HashSet::<&u8>::from_iter(p).len() == n)
-        .context("all different")?
+        .ok_or_else(|| Error::no_solution(DAY, format!("no {} different characters in a row", n)))?
         .0
         + n)
 }
 
-pub fn part1(input: String) -> usize {
-    match find_first_different(input, 4) {
-        Ok(n) => n,
-        Err(_) => unreachable!("no solution"),
-    }
+pub fn part1(input: String) -> Result<Answer> {
+    find_first_different(input, 4).map(Answer::from)
 }
 
-pub fn part2(input: String) -> usize {
-    match find_first_different(input, 14) {
-        Ok(n) => n,
-        Err(_) => unreachable!("no solution"),
-    }
+pub fn part2(input: String) -> Result<Answer> {
+    find_first_different(input, 14).map(Answer::from)
 }
@@ -1,6 +1,14 @@
+use std::collections::{BTreeMap, BTreeSet, VecDeque};
+use std::fmt;
 use std::str::FromStr;
 
-use anyhow::{anyhow, Context, Error, Result};
+use crate::answer::Answer;
+use crate::error::{Error, Result};
+use anyhow::{anyhow, Context};
+use itertools::{Either, Itertools};
+use once_cell::unsync::OnceCell;
+
+const DAY: u8 = 7;
 
 #[derive(Debug, PartialEq)]
 enum Command {
@@ -9,146 +17,565 @@ enum Command {
 }
 
 impl FromStr for Command {
-    type Err = Error;
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> anyhow::Result<Self> {
+        match s.get(..2) {
+            Some("ls") => Ok(Command::Ls),
+            Some("cd") => Ok(Command::Cd(s[2..].trim().to_owned())),
+            _ => Err(anyhow!("unknown command")),
+        }
+    }
+}
+
+/// One line of `ls` output.
+#[derive(Debug, PartialEq)]
+enum Entry {
+    Dir(String),
+    File(String, usize),
+}
+
+impl FromStr for Entry {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> anyhow::Result<Self> {
+        let (first, name) = s.split_once(' ').context("no filename")?;
+        // `cd` would read these as paths, not as names
+        if name.is_empty() || name == "." || name == ".." || name.contains('/') {
+            return Err(anyhow!("invalid name '{}'", name));
+        }
+        match first {
+            "dir" => Ok(Entry::Dir(name.to_owned())),
+            size => Ok(Entry::File(
+                name.to_owned(),
+                size.parse().context("non int size")?,
+            )),
+        }
+    }
+}
 
-    fn from_str(s: &str) -> Result<Self> {
-        match &s[..2] {
-            "ls" => Ok(Command::Ls),
-            "cd" => Ok(Command::Cd(s[2..].trim().to_owned())),
-            _ => Err(anyhow!("unreachable")),
+impl Entry {
+    fn name(&self) -> &str {
+        match self {
+            Entry::Dir(name) | Entry::File(name, _) => name,
         }
     }
 }
 
 #[derive(Default, Debug)]
 pub struct Folder {
-    id: usize,
     name: String,
-    size: usize,
     parent: Option<usize>,
-    children: Vec<usize>,
+    /// Subfolders by name.
+    children: BTreeMap<String, usize>,
+    /// File sizes by name.
+    files: BTreeMap<String, usize>,
+    /// Whether `ls` was run here, after which the content is known for good.
+    listed: bool,
 }
 
 impl Folder {
-    pub fn new(id: usize, name: String, size: usize, parent: Option<usize>) -> Self {
+    pub fn new(name: String, parent: Option<usize>) -> Self {
         Folder {
-            id,
             name: name.trim().into(),
-            size,
             parent,
-            children: vec![],
+            ..Default::default()
         }
     }
 }
 
-#[derive(Debug, Default)]
+/// The filesystem seen through a terminal session. Folders live in an arena
+/// and are referred to by their index, the root being 0. A folder is always
+/// created after its parent, so it has a larger index.
+#[derive(Debug)]
 pub struct DirectoryTree {
     arena: Vec<Folder>,
+    /// Total size of every folder, computed on the first query and then kept
+    /// up to date as files are added.
+    totals: OnceCell<Vec<usize>>,
 }
 
+impl Default for DirectoryTree {
+    fn default() -> Self {
+        DirectoryTree {
+            arena: vec![Folder::new("/".into(), None)],
+            totals: OnceCell::new(),
+        }
+    }
+}
+
+/// Two trees are equal if they have the same folders and files at the same
+/// paths, and the same folders were listed, whatever the order of the arena.
+impl PartialEq for DirectoryTree {
+    fn eq(&self, other: &Self) -> bool {
+        let mut stack = vec![(Self::ROOT, Self::ROOT)];
+        while let Some((a, b)) = stack.pop() {
+            let (a, b) = (&self.arena[a], &other.arena[b]);
+            if a.listed != b.listed
+                || a.files != b.files
+                || !a.children.keys().eq(b.children.keys())
+            {
+                return false;
+            }
+            stack.extend(
+                a.children
+                    .values()
+                    .copied()
+                    .zip(b.children.values().copied()),
+            );
+        }
+        true
+    }
+}
+
+impl Eq for DirectoryTree {}
+
 impl DirectoryTree {
-    fn folder(&mut self, name: String, parent: Option<usize>) -> usize {
-        //first see if it exists
-        for node in &self.arena {
-            if node.name == name {
-                match (parent, node.parent) {
-                    (Some(p1), Some(p2)) => {
-                        if p1 == p2 {
-                            return node.id;
-                        }
+    pub const ROOT: usize = 0;
+
+    /// The subfolder `name` of `parent`, created if the content of `parent`
+    /// is not known yet.
+    fn folder(&mut self, parent: usize, name: &str) -> anyhow::Result<usize> {
+        let node = &self.arena[parent];
+        if let Some(&id) = node.children.get(name) {
+            return Ok(id);
+        }
+        if node.files.contains_key(name) {
+            return Err(anyhow!("{} is a file", self.join(parent, name)));
+        }
+        if node.listed {
+            return Err(anyhow!("no directory {}", self.join(parent, name)));
+        }
+        let id = self.arena.len();
+        self.arena.push(Folder::new(name.into(), Some(parent)));
+        if let Some(totals) = self.totals.get_mut() {
+            totals.push(0);
+        }
+        self.arena[parent].children.insert(name.into(), id);
+        Ok(id)
+    }
+
+    /// Follow `path` from `from`, or from the root if it is absolute, the way
+    /// `cd` does.
+    fn cd(&mut self, from: usize, path: &str) -> anyhow::Result<usize> {
+        let mut current = if path.starts_with('/') {
+            Self::ROOT
+        } else {
+            from
+        };
+        for part in path.split('/').filter(|p| !p.is_empty()) {
+            current = match part {
+                "." => current,
+                ".." => self.arena[current]
+                    .parent
+                    .ok_or_else(|| anyhow!("already at the root"))?,
+                name => self.folder(current, name)?,
+            };
+        }
+        Ok(current)
+    }
+
+    /// Record the output of `ls` in `dir`. Fails if it contradicts what is
+    /// already known: an earlier listing, or a folder entered with `cd`.
+    fn list(&mut self, dir: usize, entries: Vec<Entry>) -> anyhow::Result<()> {
+        let mut names = BTreeSet::new();
+        for entry in &entries {
+            let name = entry.name();
+            if !names.insert(name) {
+                return Err(anyhow!("{} is listed twice", self.join(dir, name)));
+            }
+            let node = &self.arena[dir];
+            let known = match entry {
+                Entry::Dir(_) if node.files.contains_key(name) => {
+                    return Err(anyhow!("{} was a file", self.join(dir, name)))
+                }
+                Entry::File(..) if node.children.contains_key(name) => {
+                    return Err(anyhow!("{} was a directory", self.join(dir, name)))
+                }
+                Entry::Dir(_) => node.children.contains_key(name),
+                Entry::File(_, size) => match node.files.get(name) {
+                    Some(old) if old != size => {
+                        return Err(anyhow!(
+                            "{} was {} bytes, now {}",
+                            self.join(dir, name),
+                            old,
+                            size
+                        ))
+                    }
+                    old => old.is_some(),
+                },
+            };
+            if !known && node.listed {
+                return Err(anyhow!(
+                    "{} was not in the earlier listing",
+                    self.join(dir, name)
+                ));
+            }
+        }
+        let node = &self.arena[dir];
+        if let Some(missing) = node
+            .children
+            .keys()
+            .chain(node.files.keys())
+            .find(|name| !names.contains(name.as_str()))
+        {
+            return Err(anyhow!("{} is not listed", self.join(dir, missing)));
+        }
+
+        let mut added = 0;
+        for entry in entries {
+            match entry {
+                Entry::Dir(name) => {
+                    self.folder(dir, &name)?;
+                }
+                Entry::File(name, size) => {
+                    if self.arena[dir].files.insert(name, size).is_none() {
+                        added += size;
                     }
-                    (None, None) => return node.id,
-                    _ => (),
                 }
             }
         }
+        self.arena[dir].listed = true;
+        if let Some(totals) = self.totals.get_mut() {
+            let mut current = Some(dir);
+            while let Some(id) = current {
+                totals[id] += added;
+                current = self.arena[id].parent;
+            }
+        }
+        Ok(())
+    }
 
-        // Otherwise, add new node
-        let id = self.arena.len();
-        self.arena.push(Folder::new(id, name, 0, parent));
-        id
+    /// The folder at `path`, absolute or relative to the root.
+    pub fn lookup(&self, path: &str) -> Option<usize> {
+        path.split('/')
+            .filter(|p| !p.is_empty())
+            .try_fold(Self::ROOT, |dir, name| {
+                self.arena[dir].children.get(name).copied()
+            })
     }
 
-    fn add_child(&mut self, parent: usize, child: usize) {
-        if !self.arena[parent].children.contains(&child) {
-            self.arena[parent].children.push(child)
+    /// The absolute path of `dir`.
+    pub fn path(&self, dir: usize) -> String {
+        let mut names = vec![];
+        let mut current = dir;
+        while let Some(parent) = self.arena[current].parent {
+            names.push(self.arena[current].name.as_str());
+            current = parent;
         }
-        self.arena[child].parent = Some(parent)
+        names.reverse();
+        format!("/{}", names.join("/"))
+    }
+
+    fn join(&self, dir: usize, name: &str) -> String {
+        let path = self.path(dir);
+        format!("{}/{}", path.trim_end_matches('/'), name)
+    }
+
+    /// The files directly in `dir`, with their sizes.
+    pub fn files(&self, dir: usize) -> impl Iterator<Item = (&str, usize)> {
+        self.arena[dir]
+            .files
+            .iter()
+            .map(|(name, &size)| (name.as_str(), size))
     }
 
-    fn size(&self, id: usize) -> usize {
-        let node = &self.arena[id];
-        let mut size = node.size;
-        for child in &node.children {
-            size += self.size(*child)
+    /// The total size of the files in `dir` and below.
+    pub fn size(&self, dir: usize) -> usize {
+        self.totals()[dir]
+    }
+
+    fn totals(&self) -> &[usize] {
+        self.totals.get_or_init(|| {
+            let mut totals: Vec<usize> = self
+                .arena
+                .iter()
+                .map(|folder| folder.files.values().sum())
+                .collect();
+            // children come after their parent, so walking backwards adds
+            // every folder to its parent once it is complete
+            for (id, folder) in self.arena.iter().enumerate().rev() {
+                if let Some(parent) = folder.parent {
+                    totals[parent] += totals[id];
+                }
+            }
+            totals
+        })
+    }
+
+    /// The total size of the folder at `path`, see [`DirectoryTree::lookup`].
+    pub fn size_of(&self, path: &str) -> Option<usize> {
+        self.lookup(path).map(|dir| self.size(dir))
+    }
+
+    /// Every folder whose total size satisfies `predicate`, with that size.
+    pub fn dirs_where<'a>(
+        &'a self,
+        predicate: impl Fn(usize) -> bool + 'a,
+    ) -> impl Iterator<Item = (usize, usize)> + 'a {
+        (0..self.arena.len())
+            .map(|dir| (dir, self.size(dir)))
+            .filter(move |&(_, size)| predicate(size))
+    }
+
+    /// The `n` largest files as (path, size), largest first.
+    pub fn largest_files(&self, n: usize) -> Vec<(String, usize)> {
+        let mut files: Vec<_> = (0..self.arena.len())
+            .flat_map(|dir| {
+                self.files(dir)
+                    .map(move |(name, size)| (self.join(dir, name), size))
+            })
+            .collect();
+        files.sort_by(|(p1, s1), (p2, s2)| s2.cmp(s1).then_with(|| p1.cmp(p2)));
+        files.truncate(n);
+        files
+    }
+}
+
+/// Displays the total size of every folder, subfolders first, like `du`.
+pub struct Du<'a>(pub &'a DirectoryTree);
+
+impl fmt::Display for Du<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let Du(tree) = self;
+        // a folder is pushed again as done, to be printed after its children
+        let mut stack = vec![(DirectoryTree::ROOT, false)];
+        while let Some((dir, done)) = stack.pop() {
+            if done {
+                writeln!(f, "{}\t{}", tree.size(dir), tree.path(dir))?;
+            } else {
+                stack.push((dir, true));
+                let children = tree.arena[dir].children.values().rev();
+                stack.extend(children.map(|&child| (child, false)));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Displays the folders and files as a tree, in the format of the puzzle
+/// text.
+pub struct Tree<'a>(pub &'a DirectoryTree);
+
+impl fmt::Display for Tree<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let Tree(tree) = self;
+        let root = &tree.arena[DirectoryTree::ROOT].name;
+        let mut stack = vec![(0, root, Either::Left(DirectoryTree::ROOT))];
+        while let Some((depth, name, entry)) = stack.pop() {
+            let dir = match entry {
+                Either::Left(dir) => dir,
+                Either::Right(size) => {
+                    writeln!(
+                        f,
+                        "{:indent$}- {} (file, size={})",
+                        "",
+                        name,
+                        size,
+                        indent = 2 * depth
+                    )?;
+                    continue;
+                }
+            };
+            writeln!(f, "{:indent$}- {} (dir)", "", name, indent = 2 * depth)?;
+            let node = &tree.arena[dir];
+            // folders and files are sorted together by name
+            let entries: Vec<_> = node
+                .children
+                .iter()
+                .map(|(name, &child)| (depth + 1, name, Either::Left(child)))
+                .merge_by(
+                    node.files
+                        .iter()
+                        .map(|(name, &size)| (depth + 1, name, Either::Right(size))),
+                    |(_, n1, _), (_, n2, _)| n1 <= n2,
+                )
+                .collect();
+            stack.extend(entries.into_iter().rev());
+        }
+        Ok(())
+    }
+}
+
+/// A terminal session exploring `tree`, such that parsing it gives `tree`
+/// back. Every folder is entered, and listed if it is listed in `tree`.
+pub fn transcript(tree: &DirectoryTree) -> String {
+    let mut session = String::from("$ cd /\n");
+    // `None` goes back up to the parent
+    let mut stack = vec![Some(DirectoryTree::ROOT)];
+    while let Some(step) = stack.pop() {
+        let dir = match step {
+            Some(dir) => dir,
+            None => {
+                session.push_str("$ cd ..\n");
+                continue;
+            }
+        };
+        let node = &tree.arena[dir];
+        if dir != DirectoryTree::ROOT {
+            session.push_str(&format!("$ cd {}\n", node.name));
+            stack.push(None);
+        }
+        if node.listed {
+            session.push_str("$ ls\n");
+            for name in node.children.keys() {
+                session.push_str(&format!("dir {}\n", name));
+            }
+            for (name, size) in &node.files {
+                session.push_str(&format!("{} {}\n", size, name));
+            }
+        }
+        stack.extend(node.children.values().rev().map(|&child| Some(child)));
+    }
+    session
+}
+
+/// The shape of a random [`DirectoryTree`], to test the parser on inputs
+/// with known properties.
+#[derive(Debug, Clone, Copy)]
+pub struct RandomTree {
+    pub seed: u64,
+    /// Levels of folders below the root.
+    pub depth: usize,
+    /// The most folders, and the most files, in one folder.
+    pub fan_out: usize,
+}
+
+impl RandomTree {
+    /// Every folder of the tree is listed. The same spec always gives the
+    /// same tree.
+    pub fn generate(&self) -> DirectoryTree {
+        let mut rng = SplitMix64(self.seed);
+        let mut tree = DirectoryTree::default();
+        let mut queue = VecDeque::from([(DirectoryTree::ROOT, 0)]);
+        while let Some((dir, level)) = queue.pop_front() {
+            let mut names = BTreeSet::new();
+            let mut entries = vec![];
+            let dirs = if level < self.depth {
+                rng.below(self.fan_out + 1)
+            } else {
+                0
+            };
+            for _ in 0..dirs {
+                entries.push(Entry::Dir(rng.name(&mut names, false)));
+            }
+            for _ in 0..rng.below(self.fan_out + 1) {
+                let size = 1 + rng.below(300000);
+                entries.push(Entry::File(rng.name(&mut names, true), size));
+            }
+            tree.list(dir, entries)
+                .expect("a new folder with unique names");
+            queue.extend(
+                tree.arena[dir]
+                    .children
+                    .values()
+                    .map(|&child| (child, level + 1)),
+            );
+        }
+        tree
+    }
+}
+
+/// A small deterministic generator, so that a seed gives the same tree on
+/// every platform.
+struct SplitMix64(u64);
+
+impl SplitMix64 {
+    fn next(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9e3779b97f4a7c15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+        z ^ (z >> 31)
+    }
+
+    /// A number in `0..n`, with a negligible bias for small `n`.
+    fn below(&mut self, n: usize) -> usize {
+        (self.next() % n as u64) as usize
+    }
+
+    /// A name of a few letters not in `taken`, with an extension for files.
+    fn name(&mut self, taken: &mut BTreeSet<String>, file: bool) -> String {
+        loop {
+            let len = 1 + self.below(6);
+            let mut name: String = (0..len)
+                .map(|_| (b'a' + self.below(26) as u8) as char)
+                .collect();
+            if file && self.below(2) == 0 {
+                name.push_str([".txt", ".dat", ".log", ".lst"][self.below(4)]);
+            }
+            if taken.insert(name.clone()) {
+                return name;
+            }
         }
-        size
     }
 }
 
 pub fn parse_input(input: &str) -> Result<DirectoryTree> {
     let mut directory = DirectoryTree::default();
-    let mut current_folder = directory.folder("/".into(), None);
-    // first line is always cd / to set the root, skip it
-    for block in input.split("\n$").skip(1) {
-        // first line is a command
-        let mut lines = block.lines();
-        let command = match lines.next() {
-            Some(command) => command.trim().parse::<Command>()?,
-            None => continue,
-        };
-        match command {
-            Command::Cd(ref s) if s == ".." => {
-                // move up
-                current_folder = match directory.arena[current_folder].parent {
-                    Some(c) => c,
-                    None => Err(anyhow!("Top reach too soon with {:#?}", directory))?,
+    let mut current_folder = DirectoryTree::ROOT;
+    // the `ls` being read: its line, its folder and its output so far
+    let mut listing: Option<(usize, usize, Vec<Entry>)> = None;
+    let finish = |directory: &mut DirectoryTree, listing| -> Result<()> {
+        if let Some((line, dir, entries)) = listing {
+            directory
+                .list(dir, entries)
+                .map_err(|e| Error::parse(DAY, line, "$ ls", e))?;
+        }
+        Ok(())
+    };
+    for (i, line) in input.lines().enumerate().map(|(i, l)| (i + 1, l.trim())) {
+        if line.is_empty() {
+            continue;
+        }
+        let command = match line.strip_prefix('$') {
+            Some(command) => command.trim(),
+            None => {
+                let entry = line.parse().map_err(|e| Error::parse(DAY, i, line, e))?;
+                match listing {
+                    Some((_, _, ref mut entries)) => entries.push(entry),
+                    None => return Err(Error::parse(DAY, i, line, "output without ls")),
                 }
+                continue;
             }
-            Command::Cd(s) => {
-                // move down
-                let folder = directory.folder(s, Some(current_folder));
-                directory.add_child(current_folder, folder);
-                current_folder = folder;
-            }
-            Command::Ls => {
-                // println!("In LS");
-                for line in lines {
-                    // println!("{}", line);
-                    if let Some(stripped) = line.strip_prefix("dir") {
-                        // add a subdir
-                        let child = directory.folder(stripped.to_string(), Some(current_folder));
-                        directory.add_child(current_folder, child);
-                    } else {
-                        let (n, _) = line.split_once(' ').context("no filename")?;
-                        directory.arena[current_folder].size +=
-                            n.parse::<usize>().context("non int size")?;
-                    }
-                }
+        };
+        finish(&mut directory, listing.take())?;
+        match command
+            .parse::<Command>()
+            .map_err(|e| Error::parse(DAY, i, line, e))?
+        {
+            Command::Cd(path) => {
+                current_folder = directory
+                    .cd(current_folder, &path)
+                    .map_err(|e| Error::parse(DAY, i, line, e))?;
             }
+            Command::Ls => listing = Some((i, current_folder, vec![])),
         }
-        // println!("current folder: {:?}", current_folder);
     }
+    finish(&mut directory, listing)?;
 
     Ok(directory)
 }
 
-pub fn part1(tree: DirectoryTree) -> usize {
-    (0..tree.arena.len())
-        .map(|dir| tree.size(dir))
-        .filter(|size| size <= &100000)
-        .sum()
+pub fn part1(tree: DirectoryTree) -> Result<Answer> {
+    Ok(tree
+        .dirs_where(|size| size <= 100000)
+        .map(|(_, size)| size)
+        .sum::<usize>()
+        .into())
 }
 
-pub fn part2(tree: DirectoryTree) -> usize {
+pub fn part2(tree: DirectoryTree) -> Result<Answer> {
     const SIZE_TO_FREE: usize = 70000000;
     // 0 is the root
-    let unused_space = SIZE_TO_FREE - tree.size(0);
-    (0..tree.arena.len())
-        .map(|dir| tree.size(dir))
-        .filter(|&size| unused_space + size > 30000000)
+    let unused_space = SIZE_TO_FREE
+        .checked_sub(tree.size(DirectoryTree::ROOT))
+        .ok_or_else(|| Error::no_solution(DAY, "the files do not fit on the disk"))?;
+    tree.dirs_where(|size| unused_space + size > 30000000)
+        .map(|(_, size)| size)
         .min()
-        .unwrap()
+        .map(Answer::from)
+        .ok_or_else(|| Error::no_solution(DAY, "no directory frees enough space"))
 }
 
 #[cfg(test)]
@@ -190,12 +617,14 @@ $ ls
         assert_eq!(&dir.arena.len(), &4);
         let root = &dir.arena[0];
         assert_eq!(root.name, "/");
-        // size is only the files directly in the folder
-        assert_eq!(root.size, 23352670);
+        // files directly in the folder
+        assert_eq!(
+            dir.files(0).collect::<Vec<_>>(),
+            [("b.txt", 14848514), ("c.dat", 8504156)]
+        );
         // '/' has 2 children
         assert_eq!(root.children.len(), 2);
-        let a = &dir.arena[1];
-        assert_eq!(a.size, 94269);
+        assert_eq!(dir.files(1).map(|(_, size)| size).sum::<usize>(), 94269);
         // total sizes
         assert_eq!(dir.size(0), 48381165); // '/4
         assert_eq!(dir.size(1), 94853); // 'a'
@@ -207,14 +636,232 @@ $ ls
     #[test]
     fn test_part1() -> Result<()> {
         let dir = parse_input(INPUT)?;
-        assert_eq!(part1(dir), 95437);
+        assert_eq!(part1(dir)?, 95437.into());
         Ok(())
     }
 
     #[test]
     fn test_part2() -> Result<()> {
         let dir = parse_input(INPUT)?;
-        assert_eq!(part2(dir), 24933642);
+        assert_eq!(part2(dir)?, 24933642.into());
+        Ok(())
+    }
+
+    #[test]
+    fn test_paths() -> Result<()> {
+        let dir = parse_input(INPUT)?;
+        assert_eq!(dir.lookup("/"), Some(0));
+        assert_eq!(dir.lookup("/a/e"), Some(3));
+        assert_eq!(dir.lookup("a/e/"), Some(3));
+        assert_eq!(dir.lookup("/e"), None);
+        assert_eq!(dir.path(3), "/a/e");
+        assert_eq!(dir.path(0), "/");
+        Ok(())
+    }
+
+    #[test]
+    fn test_absolute_cd() -> Result<()> {
+        let session = "$ cd /a/e
+$ ls
+584 i
+$ cd /
+$ ls
+dir a
+$ cd a
+$ cd ../a/./e
+$ ls
+584 i";
+        let dir = parse_input(session)?;
+        assert_eq!(dir.lookup("/a/e"), Some(2));
+        assert_eq!(dir.size(0), 584);
+        Ok(())
+    }
+
+    #[test]
+    fn test_contradictions() {
+        let error = |session: &str| parse_input(session).unwrap_err().to_string();
+        assert_eq!(
+            error("$ ls\n1 a\n$ ls\n2 a"),
+            "day 7, line 3: /a was 1 bytes, now 2 in '$ ls'"
+        );
+        assert_eq!(
+            error("$ ls\n1 a\n$ ls\n1 a\n2 b"),
+            "day 7, line 3: /b was not in the earlier listing in '$ ls'"
+        );
+        assert_eq!(
+            error("$ ls\n1 a\n2 b\n$ ls\n2 b"),
+            "day 7, line 4: /a is not listed in '$ ls'"
+        );
+        assert_eq!(
+            error("$ cd x\n$ cd /\n$ ls\n5 x"),
+            "day 7, line 3: /x was a directory in '$ ls'"
+        );
+        assert_eq!(
+            error("$ ls\n1 a\n1 a"),
+            "day 7, line 1: /a is listed twice in '$ ls'"
+        );
+        assert_eq!(
+            error("$ ls\ndir a\n$ cd b"),
+            "day 7, line 3: no directory /b in '$ cd b'"
+        );
+        assert_eq!(
+            error("$ cd ..\n"),
+            "day 7, line 1: already at the root in '$ cd ..'"
+        );
+        assert_eq!(
+            error("dir a"),
+            "day 7, line 1: output without ls in 'dir a'"
+        );
+    }
+
+    #[test]
+    fn test_invalid_names() {
+        let error = |session: &str| parse_input(session).unwrap_err().to_string();
+        assert_eq!(
+            error("$ cd /\n$ ls\ndir a/b\n"),
+            "day 7, line 3: invalid name 'a/b' in 'dir a/b'"
+        );
+        assert_eq!(
+            error("$ ls\n1 ..\n"),
+            "day 7, line 2: invalid name '..' in '1 ..'"
+        );
+        assert_eq!(
+            error("$ ls\ndir .\n"),
+            "day 7, line 2: invalid name '.' in 'dir .'"
+        );
+    }
+
+    #[test]
+    fn test_queries() -> Result<()> {
+        let dir = parse_input(INPUT)?;
+        assert_eq!(dir.size_of("/a"), Some(94853));
+        assert_eq!(dir.size_of("/a/e/i"), None);
+        let small: Vec<_> = dir
+            .dirs_where(|size| size <= 100000)
+            .map(|(d, _)| dir.path(d))
+            .collect();
+        assert_eq!(small, ["/a", "/a/e"]);
+        assert_eq!(
+            dir.largest_files(2),
+            [("/b.txt".into(), 14848514), ("/c.dat".into(), 8504156)]
+        );
+        assert_eq!(dir.largest_files(20).len(), 10);
+        Ok(())
+    }
+
+    #[test]
+    fn test_du() -> Result<()> {
+        let dir = parse_input(INPUT)?;
+        assert_eq!(
+            Du(&dir).to_string(),
+            "584\t/a/e\n94853\t/a\n24933642\t/d\n48381165\t/\n"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_tree() -> Result<()> {
+        let dir = parse_input(INPUT)?;
+        // as drawn in the puzzle text
+        let expected = "- / (dir)
+  - a (dir)
+    - e (dir)
+      - i (file, size=584)
+    - f (file, size=29116)
+    - g (file, size=2557)
+    - h.lst (file, size=62596)
+  - b.txt (file, size=14848514)
+  - c.dat (file, size=8504156)
+  - d (dir)
+    - d.ext (file, size=5626152)
+    - d.log (file, size=8033020)
+    - j (file, size=4060174)
+    - k (file, size=7214296)
+";
+        assert_eq!(Tree(&dir).to_string(), expected);
+        Ok(())
+    }
+
+    #[test]
+    fn test_incremental_sizes() -> Result<()> {
+        let mut dir = parse_input("$ cd a\n$ ls\n1 x")?;
+        assert_eq!(dir.size(DirectoryTree::ROOT), 1);
+        let b = dir
+            .cd(DirectoryTree::ROOT, "/b")
+            .map_err(|e| Error::input(DAY, e))?;
+        dir.list(b, vec![Entry::File("y".into(), 2)])
+            .map_err(|e| Error::input(DAY, e))?;
+        assert_eq!(dir.size_of("/b"), Some(2));
+        assert_eq!(dir.size_of("/a"), Some(1));
+        assert_eq!(dir.size(DirectoryTree::ROOT), 3);
+        Ok(())
+    }
+
+    #[test]
+    fn test_deep_tree() -> Result<()> {
+        let depth = 20000;
+        let session = "$ ls\ndir d\n1 f\n$ cd d\n".repeat(depth);
+        let dir = parse_input(&session)?;
+        assert_eq!(dir.size(DirectoryTree::ROOT), depth);
+        assert_eq!(part1(dir)?, (depth * (depth + 1) / 2).into());
+
+        // the output is quadratic in the depth, so render a shallower chain,
+        // still far too deep for recursion, and only count its lines
+        struct Lines(usize);
+        impl fmt::Write for Lines {
+            fn write_str(&mut self, s: &str) -> fmt::Result {
+                self.0 += s.matches('\n').count();
+                Ok(())
+            }
+
+            fn write_char(&mut self, c: char) -> fmt::Result {
+                self.0 += usize::from(c == '\n');
+                Ok(())
+            }
+        }
+        let depth = 3000;
+        let dir = parse_input(&"$ ls\ndir d\n1 f\n$ cd d\n".repeat(depth))?;
+        let mut lines = Lines(0);
+        fmt::write(&mut lines, format_args!("{}", Du(&dir))).unwrap();
+        assert_eq!(lines.0, depth + 1);
+        let mut lines = Lines(0);
+        fmt::write(&mut lines, format_args!("{}", Tree(&dir))).unwrap();
+        assert_eq!(lines.0, 2 * depth + 1);
+        Ok(())
+    }
+
+    #[test]
+    fn test_transcript() -> Result<()> {
+        let dir = parse_input(INPUT)?;
+        let session = transcript(&dir);
+        assert!(session.starts_with("$ cd /\n$ ls\ndir a\ndir d\n14848514 b.txt\n"));
+        assert_eq!(parse_input(&session)?, dir);
+        // a folder entered without `ls` stays unlisted
+        let dir = parse_input("$ cd a\n$ cd b\n$ ls\n1 x")?;
+        assert_eq!(
+            transcript(&dir),
+            "$ cd /\n$ cd a\n$ cd b\n$ ls\n1 x\n$ cd ..\n$ cd ..\n"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_random_transcripts() -> Result<()> {
+        let mut folders = 0;
+        for seed in 0..50 {
+            let spec = RandomTree {
+                seed,
+                depth: 4,
+                fan_out: 3,
+            };
+            let tree = spec.generate();
+            assert_eq!(spec.generate(), tree);
+            let parsed = parse_input(&transcript(&tree))?;
+            assert_eq!(parsed, tree, "seed {}", seed);
+            assert_eq!(Du(&parsed).to_string(), Du(&tree).to_string());
+            folders += tree.arena.len();
+        }
+        assert!(folders > 500);
         Ok(())
     }
 }
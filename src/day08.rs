@@ -1,86 +1,137 @@
-use anyhow::{anyhow, Result};
-use num::complex::Complex;
-use std::collections::HashMap;
-
-type Tree = Complex<isize>;
-type Grid = HashMap<Tree, u32>;
-
-pub fn parse_input(input: &str) -> Result<Grid> {
-    input
-        .lines()
-        .enumerate()
-        .flat_map(|(j, l)| {
-            l.chars().enumerate().map(move |(i, c)| {
-                Ok((
-                    Complex::new(i.try_into()?, j.try_into()?),
-                    c.to_digit(10)
-                        .ok_or_else(|| anyhow!("non digit char {}", c))?,
-                ))
-            })
-        })
-        .collect()
-}
+use crate::answer::{Answer, Bitmap};
+use crate::error::{Error, Result};
+use crate::grid::Grid;
 
-fn is_visible(grid: &Grid, tree: &Tree, height: &u32) -> bool {
-    grid.iter()
-        .filter(|(k, _)| (k.im < tree.im) && (k.re == tree.re))
-        .all(|(_, v)| v < height)
-        || grid
-            .iter()
-            .filter(|(k, _)| (k.im > tree.im) && (k.re == tree.re))
-            .all(|(_, v)| v < height)
-        || grid
-            .iter()
-            .filter(|(k, _)| (k.im == tree.im) && (k.re < tree.re))
-            .all(|(_, v)| v < height)
-        || grid
-            .iter()
-            .filter(|(k, _)| (k.im == tree.im) && (k.re > tree.re))
-            .all(|(_, v)| v < height)
+const DAY: u8 = 8;
+
+/// What can be seen from every tree, in the layout of the grid.
+struct Survey {
+    /// Whether the tree can be seen from outside the forest.
+    visible: Vec<bool>,
+    scenic_scores: Vec<usize>,
 }
 
-pub fn part1(grid: Grid) -> usize {
-    grid.iter()
-        .filter(|&(k, v)| is_visible(&grid, k, v))
-        .count()
+impl Grid<u8> {
+    /// Whether each tree can be seen from outside the forest, as a bitmap
+    /// that [`Render`](crate::render::Render) can draw.
+    pub fn visibility(&self) -> Bitmap {
+        Grid::new(self.width(), self.survey().visible)
+    }
+
+    /// The product of the viewing distances of each tree in the four
+    /// directions.
+    pub fn scenic_scores(&self) -> Grid<usize> {
+        Grid::new(self.width(), self.survey().scenic_scores)
+    }
+
+    /// Look along every row and column, in both directions. The stack holds
+    /// the trees seen so far that are not hidden behind a taller or equal one,
+    /// so the tree blocking the view is on top once the shorter ones are
+    /// popped. Each tree is pushed and popped once per direction, so the whole
+    /// survey is linear in the number of trees.
+    fn survey(&self) -> Survey {
+        let (width, height) = (self.width(), self.height());
+        let mut survey = Survey {
+            visible: vec![false; self.cells().len()],
+            scenic_scores: vec![1; self.cells().len()],
+        };
+        let mut stack = vec![];
+        for y in 0..height {
+            self.look(&mut survey, &mut stack, width, |k| y * width + k);
+            self.look(&mut survey, &mut stack, width, |k| {
+                y * width + width - 1 - k
+            });
+        }
+        for x in 0..width {
+            self.look(&mut survey, &mut stack, height, |k| k * width + x);
+            self.look(&mut survey, &mut stack, height, |k| {
+                (height - 1 - k) * width + x
+            });
+        }
+        survey
+    }
+
+    /// Look from the start of a line of `len` trees, the `k`-th being at
+    /// `index(k)` in the grid.
+    fn look(
+        &self,
+        survey: &mut Survey,
+        stack: &mut Vec<(usize, u8)>,
+        len: usize,
+        index: impl Fn(usize) -> usize,
+    ) {
+        stack.clear();
+        for k in 0..len {
+            let i = index(k);
+            let height = self.cells()[i];
+            while matches!(stack.last(), Some(&(_, h)) if h < height) {
+                stack.pop();
+            }
+            let distance = match stack.last() {
+                Some(&(blocker, _)) => k - blocker,
+                None => {
+                    survey.visible[i] = true;
+                    k
+                }
+            };
+            survey.scenic_scores[i] *= distance;
+            stack.push((k, height));
+        }
+    }
 }
 
-fn view(grid: &Grid, direction: &Tree, tree: &Tree, height: &u32) -> isize {
-    let mut view = 0;
-    let mut new_tree = tree + direction;
-    loop {
-        match grid.get(&new_tree) {
-            None => return view,
-            Some(h) if h < height => {
-                new_tree += direction;
-                view += 1;
+pub fn parse_input(input: &str) -> Result<Grid<u8>> {
+    let mut width = None;
+    let mut cells = vec![];
+    for (j, l) in input.lines().enumerate() {
+        for c in l.chars() {
+            let height = c
+                .to_digit(10)
+                .ok_or_else(|| Error::parse(DAY, j + 1, l, format!("non digit char {}", c)))?;
+            cells.push(height as u8);
+        }
+        match width {
+            None => width = Some(l.len()),
+            Some(w) if w != l.len() => {
+                return Err(Error::parse(
+                    DAY,
+                    j + 1,
+                    l,
+                    format!("row of {} trees, expected {}", l.len(), w),
+                ))
             }
-            Some(_) => return view + 1,
+            _ => (),
         }
     }
+    match width {
+        Some(width) if width > 0 => Ok(Grid::new(width, cells)),
+        _ => Err(Error::input(DAY, "no trees")),
+    }
+}
+
+pub fn part1(grid: Grid<u8>) -> Result<Answer> {
+    Ok(grid
+        .survey()
+        .visible
+        .into_iter()
+        .filter(|&v| v)
+        .count()
+        .into())
 }
 
-pub fn part2(grid: Grid) -> isize {
-    const DIRECTIONS: &[Complex<isize>; 4] = &[
-        Complex::new(0, 1),
-        Complex::new(1, 0),
-        Complex::new(-1, 0),
-        Complex::new(0, -1),
-    ];
-    grid.iter()
-        .map(|(k, v)| {
-            DIRECTIONS
-                .iter()
-                .fold(1, |acc, d| acc * view(&grid, d, k, v))
-        })
+pub fn part2(grid: Grid<u8>) -> Result<Answer> {
+    grid.survey()
+        .scenic_scores
+        .into_iter()
         .max()
-        .unwrap()
+        .map(Answer::from)
+        .ok_or_else(|| Error::no_solution(DAY, "no trees"))
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use num::complex::Complex;
+    use crate::render::{Format, Heatmap, Render};
     const INPUT: &str = "30373
 25512
 65332
@@ -88,60 +139,123 @@ mod tests {
 35390
 ";
 
-    #[test]
-    fn test_parse_input() {
-        let grid = parse_input(INPUT);
-        assert!(grid.is_ok());
-        if let Ok(g) = grid {
-            assert_eq!(g[&Complex::new(0, 0)], 3);
-            assert_eq!(g[&Complex::new(4, 4)], 0);
+    /// Walk from every tree in every direction, as the puzzle describes it.
+    fn naive_survey(grid: &Grid<u8>) -> Survey {
+        let (width, height) = (grid.width() as isize, grid.height() as isize);
+        let mut survey = Survey {
+            visible: vec![],
+            scenic_scores: vec![],
+        };
+        for y in 0..height {
+            for x in 0..width {
+                let tree = grid.get(x as usize, y as usize).unwrap();
+                let mut visible = false;
+                let mut score = 1;
+                for (dx, dy) in [(0, 1), (1, 0), (-1, 0), (0, -1)] {
+                    let (mut i, mut j) = (x + dx, y + dy);
+                    let mut distance = 0;
+                    loop {
+                        if i < 0 || j < 0 || i >= width || j >= height {
+                            visible = true;
+                            break;
+                        }
+                        distance += 1;
+                        if grid.get(i as usize, j as usize).unwrap() >= tree {
+                            break;
+                        }
+                        i += dx;
+                        j += dy;
+                    }
+                    score *= distance;
+                }
+                survey.visible.push(visible);
+                survey.scenic_scores.push(score);
+            }
         }
+        survey
     }
 
     #[test]
-    fn test_is_visible() {
-        if let Ok(g) = parse_input(INPUT) {
-            assert!(is_visible(&g, &Complex::new(0, 0), &3));
-            assert!(is_visible(&g, &Complex::new(4, 4), &0));
-            assert!(is_visible(&g, &Complex::new(1, 1), &5));
-            assert!(!is_visible(&g, &Complex::new(3, 3), &4));
-        }
+    fn test_parse_input() -> Result<()> {
+        let g = parse_input(INPUT)?;
+        assert_eq!((g.width(), g.height()), (5, 5));
+        assert_eq!(g.get(0, 0), Some(&3));
+        assert_eq!(g.get(4, 4), Some(&0));
+        assert_eq!(g.get(5, 0), None);
+        assert_eq!(
+            parse_input("123\n12\n").unwrap_err().to_string(),
+            "day 8, line 2: row of 2 trees, expected 3 in '12'"
+        );
+        assert!(parse_input("").is_err());
+        Ok(())
     }
 
     #[test]
-    fn test_part1() {
-        if let Ok(g) = parse_input(INPUT) {
-            assert_eq!(part1(g), 21);
-        }
+    fn test_survey() -> Result<()> {
+        let g = parse_input(INPUT)?;
+        let survey = g.survey();
+        let at = |x: usize, y: usize| y * g.width() + x;
+        assert!(survey.visible[at(0, 0)]);
+        assert!(survey.visible[at(4, 4)]);
+        assert!(survey.visible[at(1, 1)]);
+        assert!(!survey.visible[at(3, 3)]);
+        assert_eq!(survey.scenic_scores[at(2, 1)], 4);
+        assert_eq!(survey.scenic_scores[at(2, 3)], 8);
+        Ok(())
     }
 
     #[test]
-    fn test_view_left() {
-        let direction = Complex::new(-1_isize, 0);
-        if let Ok(g) = parse_input(INPUT) {
-            assert_eq!(view(&g, &direction, &Complex::new(0, 0), &3), 0);
-            assert_eq!(view(&g, &direction, &Complex::new(1, 0), &0), 1);
-            assert_eq!(view(&g, &direction, &Complex::new(2, 0), &3), 2);
-            assert_eq!(view(&g, &direction, &Complex::new(3, 0), &7), 3);
-            assert_eq!(view(&g, &direction, &Complex::new(4, 0), &3), 1);
+    fn test_survey_against_naive() -> Result<()> {
+        let mut seed = 7u32;
+        for (width, height) in [(1, 1), (1, 6), (6, 1), (9, 7), (20, 20)] {
+            let cells = (0..width * height)
+                .map(|_| {
+                    seed = seed.wrapping_mul(1103515245).wrapping_add(12345);
+                    (seed >> 16) as u8 % 10
+                })
+                .collect();
+            let g = Grid::new(width, cells);
+            let (fast, naive) = (g.survey(), naive_survey(&g));
+            assert_eq!(fast.visible, naive.visible);
+            assert_eq!(fast.scenic_scores, naive.scenic_scores);
         }
+        Ok(())
     }
+
     #[test]
-    fn test_view_top() {
-        let direction = Complex::new(0_isize, -1);
-        if let Ok(g) = parse_input(INPUT) {
-            assert_eq!(view(&g, &direction, &Complex::new(4, 0), &3), 0);
-            assert_eq!(view(&g, &direction, &Complex::new(4, 1), &2), 1);
-            assert_eq!(view(&g, &direction, &Complex::new(4, 2), &2), 1);
-            assert_eq!(view(&g, &direction, &Complex::new(4, 3), &9), 3);
-            assert_eq!(view(&g, &direction, &Complex::new(4, 4), &0), 1);
-        }
+    fn test_matrices() -> Result<()> {
+        let g = parse_input(INPUT)?;
+        let visible = g.visibility();
+        assert_eq!(
+            visible.rows().nth(3),
+            Some(&[true, false, true, false, true][..])
+        );
+        let scores = g.scenic_scores();
+        assert_eq!(scores.rows().nth(3), Some(&[0, 1, 8, 3, 0][..]));
+        assert_eq!(
+            Render(&visible, Format::Pbm).to_string(),
+            "P1\n5 5\n1 1 1 1 1 1 1 1 0 1 1 1 0 1 1 1 0 1 0 1 1 1 1 1 1\n"
+        );
+        assert_eq!(
+            Heatmap(&scores, Format::Pgm).to_string(),
+            "P2\n5 5\n255\n0 0 0 0 0 0 31 127 31 0 0 191 31 63 0 0 31 255 95 0 0 0 0 0 0\n"
+        );
+        assert_eq!(
+            Heatmap(&scores, Format::Ansi).to_string().lines().count(),
+            5
+        );
+        Ok(())
     }
 
     #[test]
-    fn test_part2() {
-        if let Ok(g) = parse_input(INPUT) {
-            assert_eq!(part2(g), 8);
-        }
+    fn test_part1() -> Result<()> {
+        assert_eq!(part1(parse_input(INPUT)?)?, 21.into());
+        Ok(())
+    }
+
+    #[test]
+    fn test_part2() -> Result<()> {
+        assert_eq!(part2(parse_input(INPUT)?)?, 8.into());
+        Ok(())
     }
 }
@@ -1,77 +1,212 @@
-use anyhow::{Context, Result};
+use crate::answer::Answer;
+use crate::error::{parse_lines, Result};
+use anyhow::{anyhow, Context};
+use itertools::{Itertools, MinMaxResult};
 use num::complex::Complex;
 use std::collections::HashSet;
+use std::fmt;
 
-type Position = Complex<isize>;
-type Moves = Vec<(Position, isize)>;
-type Grid = HashSet<Position>;
+const DAY: u8 = 9;
 
+pub type Position = Complex<isize>;
+/// A direction and how many steps to take.
+pub type Moves = Vec<(Position, isize)>;
+
+/// Parse moves like `R 4`, or diagonal ones like `UR 2`.
 pub fn parse_input(input: &str) -> Result<Moves> {
-    input
-        .lines()
-        .map(|l| {
-            let (d, q) = l.split_once(' ').context("wrong input")?;
-            let d = match d {
-                "R" => Complex::new(1, 0),
-                "L" => Complex::new(-1, 0),
-                "U" => Complex::new(0, 1),
-                "D" => Complex::new(0, -1),
-                _ => unreachable!("unknown move"),
+    parse_lines(DAY, input, |l| {
+        let (d, q) = l.split_once(' ').context("wrong input")?;
+        let mut direction: Position = Complex::new(0, 0);
+        for c in d.chars() {
+            direction += match c {
+                'R' => Complex::new(1, 0),
+                'L' => Complex::new(-1, 0),
+                'U' => Complex::new(0, 1),
+                'D' => Complex::new(0, -1),
+                _ => return Err(anyhow!("unknown move {}", d)),
             };
-            Ok((d, q.parse()?))
-        })
-        .collect()
+        }
+        // at most one vertical and one horizontal letter, in either order
+        if !(1..=2).contains(&d.len())
+            || direction.re.abs() > 1
+            || direction.im.abs() > 1
+            || direction.l1_norm() != d.len() as isize
+        {
+            return Err(anyhow!("unknown move {}", d));
+        }
+        anyhow::Ok((direction, q.parse()?))
+    })
 }
 
-fn move_tail(head: &Position, tail: &Position) -> Complex<isize> {
-    let diff = head - tail;
-    match diff.l1_norm() {
-        0 | 1 => Complex::new(0, 0),
-        2 => {
-            if diff.im == 0 {
-                Complex::new(diff.re.signum(), 0)
-            } else if diff.re == 0 {
-                Complex::new(0, diff.im.signum())
-            } else {
-                // diagonal case, we do not need to move
-                Complex::new(0, 0)
-            }
+/// When a knot is too far from the one before it, and how it catches up.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Follow {
+    /// Knots touch diagonally too, and move diagonally to catch up, as in
+    /// the puzzle.
+    #[default]
+    Chebyshev,
+    /// Knots touch only along rows and columns, and catch up one row or
+    /// column at a time.
+    Manhattan,
+}
+
+impl Follow {
+    fn distance(self, diff: Position) -> isize {
+        match self {
+            Follow::Chebyshev => diff.re.abs().max(diff.im.abs()),
+            Follow::Manhattan => diff.l1_norm(),
         }
-        _ => {
-            // diagonal case, we need to move towards head diagonally
-            Complex::new(diff.re.signum(), diff.im.signum())
+    }
+
+    /// Where `knot` ends up when pulled by `head`, if they can be at most
+    /// `slack` apart.
+    fn pull(self, head: Position, mut knot: Position, slack: isize) -> Position {
+        loop {
+            let diff = head - knot;
+            if self.distance(diff) <= slack {
+                return knot;
+            }
+            knot += match self {
+                Follow::Chebyshev => Complex::new(diff.re.signum(), diff.im.signum()),
+                // along the longer side first, so the rope does not cut corners
+                Follow::Manhattan if diff.re.abs() >= diff.im.abs() => {
+                    Complex::new(diff.re.signum(), 0)
+                }
+                Follow::Manhattan => Complex::new(0, diff.im.signum()),
+            };
         }
     }
 }
 
-pub fn part1(input: Moves) -> usize {
-    let (mut head, mut tail) = (Complex::new(0, 0), Complex::new(0, 0));
-    let mut grid = Grid::new();
-    grid.insert(tail);
-    for (dir, qty) in input {
-        for _ in 0..qty {
-            head += dir;
-            tail += move_tail(&head, &tail);
-            grid.insert(tail);
+/// Knots tied one after the other, the first one being the head. Every knot
+/// starts at the origin and its whole trajectory is kept.
+#[derive(Debug, Clone)]
+pub struct Rope {
+    /// Positions of every knot after each step, one `Vec` per knot.
+    trajectories: Vec<Vec<Position>>,
+    /// How far each knot can be from the one before it, the head's being
+    /// unused.
+    slack: Vec<isize>,
+    follow: Follow,
+}
+
+impl Rope {
+    /// A rope with links of length 1 following the puzzle's rule. Panics if
+    /// there are no knots.
+    pub fn new(knots: usize) -> Self {
+        assert!(knots > 0, "a rope needs at least one knot");
+        Rope {
+            trajectories: vec![vec![Complex::new(0, 0)]; knots],
+            slack: vec![1; knots],
+            follow: Follow::default(),
+        }
+    }
+
+    pub fn with_follow(mut self, follow: Follow) -> Self {
+        self.follow = follow;
+        self
+    }
+
+    /// Let knot `k` be up to `slack` away from knot `k - 1`. Panics if there
+    /// is no such link.
+    pub fn with_slack(mut self, k: usize, slack: isize) -> Self {
+        assert!(
+            k > 0 && k < self.knot_count() && slack >= 0,
+            "no link {} with a slack of {}",
+            k,
+            slack
+        );
+        self.slack[k] = slack;
+        self
+    }
+
+    pub fn knot_count(&self) -> usize {
+        self.trajectories.len()
+    }
+
+    /// Current position of every knot, head first.
+    pub fn knots(&self) -> Vec<Position> {
+        self.trajectories.iter().map(|t| t[t.len() - 1]).collect()
+    }
+
+    /// Move the head one step in `direction`, which may be diagonal, then
+    /// each knot after the one before it.
+    pub fn step(&mut self, direction: Position) {
+        let mut previous: Option<Position> = None;
+        for (trajectory, &slack) in self.trajectories.iter_mut().zip(&self.slack) {
+            let knot = trajectory[trajectory.len() - 1];
+            let knot = match previous {
+                None => knot + direction,
+                Some(head) => self.follow.pull(head, knot, slack),
+            };
+            trajectory.push(knot);
+            previous = Some(knot);
         }
     }
-    grid.len()
-}
 
-pub fn part2(input: Moves) -> usize {
-    let mut knots = [Complex::<isize>::new(0, 0); 10];
-    let mut grid = Grid::new();
-    grid.insert(knots[9]);
-    for (dir, qty) in input {
-        for _ in 0..qty {
-            knots[0] += dir;
-            for i in 1..10 {
-                knots[i] += move_tail(&knots[i - 1], &knots[i])
+    pub fn apply(&mut self, moves: &Moves) {
+        for &(direction, steps) in moves {
+            for _ in 0..steps {
+                self.step(direction);
             }
-            grid.insert(knots[9]);
         }
     }
-    grid.len()
+
+    /// Positions of knot `k` from the start, one per step. Panics if there is
+    /// no such knot.
+    pub fn trajectory(&self, k: usize) -> &[Position] {
+        &self.trajectories[k]
+    }
+
+    /// The cells knot `k` has been on.
+    pub fn visited(&self, k: usize) -> HashSet<Position> {
+        self.trajectory(k).iter().copied().collect()
+    }
+}
+
+/// Displays the cells visited by a knot of a rope as in the puzzle text,
+/// `#` for a visited cell and `s` for the start, up being north.
+pub struct Visited<'a>(pub &'a Rope, pub usize);
+
+impl fmt::Display for Visited<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let Visited(rope, k) = self;
+        let visited = rope.visited(*k);
+        let start = Complex::new(0, 0);
+        let (x_min, x_max) = match visited.iter().map(|p| p.re).minmax() {
+            MinMaxResult::MinMax(min, max) => (min, max),
+            _ => (0, 0),
+        };
+        let (y_min, y_max) = match visited.iter().map(|p| p.im).minmax() {
+            MinMaxResult::MinMax(min, max) => (min, max),
+            _ => (0, 0),
+        };
+        for y in (y_min..=y_max).rev() {
+            let line: String = (x_min..=x_max)
+                .map(|x| match Complex::new(x, y) {
+                    p if p == start => 's',
+                    p if visited.contains(&p) => '#',
+                    _ => '.',
+                })
+                .collect();
+            writeln!(f, "{}", line)?;
+        }
+        Ok(())
+    }
+}
+
+fn visited_by_tail(moves: &Moves, knots: usize) -> usize {
+    let mut rope = Rope::new(knots);
+    rope.apply(moves);
+    rope.visited(knots - 1).len()
+}
+
+pub fn part1(input: Moves) -> Result<Answer> {
+    Ok(visited_by_tail(&input, 2).into())
+}
+
+pub fn part2(input: Moves) -> Result<Answer> {
+    Ok(visited_by_tail(&input, 10).into())
 }
 
 #[cfg(test)]
@@ -79,6 +214,10 @@ mod tests {
     use super::*;
     use num::complex::Complex;
 
+    fn move_tail(head: &Position, tail: &Position) -> Position {
+        Follow::Chebyshev.pull(*head, *tail, 1) - tail
+    }
+
     #[test]
     fn test_move_tail() {
         let tail = Complex::new(0, 0);
@@ -114,6 +253,52 @@ mod tests {
         assert_eq!(move_tail(&Complex::new(2, -1), &tail), Complex::new(1, -1));
     }
 
+    #[test]
+    fn test_follow_rules() {
+        let knot = Complex::new(0, 0);
+        // diagonal neighbours do not touch
+        assert_eq!(
+            Follow::Manhattan.pull(Complex::new(1, 1), knot, 1),
+            Complex::new(1, 0)
+        );
+        assert_eq!(
+            Follow::Manhattan.pull(Complex::new(1, 3), knot, 1),
+            Complex::new(1, 2)
+        );
+        // a longer link pulls only once stretched
+        assert_eq!(Follow::Chebyshev.pull(Complex::new(3, 1), knot, 3), knot);
+        assert_eq!(
+            Follow::Chebyshev.pull(Complex::new(4, 1), knot, 3),
+            Complex::new(1, 1)
+        );
+        assert_eq!(
+            Follow::Manhattan.pull(Complex::new(4, 1), knot, 3),
+            Complex::new(2, 0)
+        );
+        // no slack at all keeps the knots together
+        assert_eq!(
+            Follow::Chebyshev.pull(Complex::new(2, -1), knot, 0),
+            Complex::new(2, -1)
+        );
+    }
+
+    #[test]
+    fn test_diagonal_moves() -> Result<()> {
+        let moves = parse_input("UR 3\nLD 1\n")?;
+        assert_eq!(moves, [(Complex::new(1, 1), 3), (Complex::new(-1, -1), 1)]);
+        assert!(parse_input("UD 1").is_err());
+        assert!(parse_input("RR 1").is_err());
+        assert!(parse_input("URL 1").is_err());
+        assert!(parse_input(" 1").is_err());
+        let mut rope = Rope::new(3).with_follow(Follow::Manhattan).with_slack(2, 2);
+        rope.apply(&moves);
+        assert_eq!(
+            rope.knots(),
+            [Complex::new(2, 2), Complex::new(3, 2), Complex::new(2, 1)]
+        );
+        Ok(())
+    }
+
     #[test]
     fn test_part1() -> Result<()> {
         let grid = parse_input(
@@ -127,7 +312,7 @@ L 5
 R 2
 ",
         )?;
-        assert_eq!(part1(grid), 13);
+        assert_eq!(part1(grid)?, 13.into());
         Ok(())
     }
 
@@ -144,7 +329,59 @@ L 25
 U 20
 ",
         )?;
-        assert_eq!(part2(grid), 36);
+        assert_eq!(part2(grid)?, 36.into());
+        Ok(())
+    }
+
+    #[test]
+    fn test_rope() -> Result<()> {
+        let moves = parse_input("R 4\nU 4\nL 3\nD 1\nR 4\nD 1\nL 5\nR 2\n")?;
+        let mut rope = Rope::new(2);
+        rope.apply(&moves);
+        assert_eq!(rope.knot_count(), 2);
+        assert_eq!(rope.knots(), [Complex::new(2, 2), Complex::new(1, 2)]);
+        // the start and one position per step
+        assert_eq!(rope.trajectory(0).len(), 25);
+        assert_eq!(
+            &rope.trajectory(1)[..6],
+            &[
+                Complex::new(0, 0),
+                Complex::new(0, 0),
+                Complex::new(1, 0),
+                Complex::new(2, 0),
+                Complex::new(3, 0),
+                Complex::new(3, 0)
+            ]
+        );
+        assert_eq!(rope.visited(0).len(), 21);
+        assert_eq!(
+            Visited(&rope, 1).to_string(),
+            "..##.\n...##\n.####\n....#\ns###.\n"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_visited_larger_rope() -> Result<()> {
+        let moves = parse_input("R 5\nU 8\nL 8\nD 3\nR 17\nD 10\nL 25\nU 20\n")?;
+        let mut rope = Rope::new(10);
+        rope.apply(&moves);
+        // as drawn in the puzzle text, without the empty border
+        let expected = "\
+#.....................
+#.............###.....
+#............#...#....
+.#..........#.....#...
+..#..........#.....#..
+...#........#.......#.
+....#......s.........#
+.....#..............#.
+......#............#..
+.......#..........#...
+........#........#....
+.........########.....
+";
+        assert_eq!(Visited(&rope, 9).to_string(), expected);
         Ok(())
     }
 }
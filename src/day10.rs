@@ -1,75 +1,265 @@
-use anyhow::Result;
+use crate::answer::{Answer, Bitmap};
+use crate::error::{parse_lines, Result};
+use crate::ocr;
+use anyhow::anyhow;
+use std::collections::BTreeSet;
 
-#[derive(Debug)]
+const DAY: u8 = 10;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Instr {
     Noop,
     Addx(isize),
 }
 
+impl Instr {
+    /// How many cycles the instruction takes to complete.
+    pub fn cycles(&self) -> usize {
+        match self {
+            Instr::Noop => 1,
+            Instr::Addx(_) => 2,
+        }
+    }
+}
+
 pub fn parse_input(input: &str) -> Result<Vec<Instr>> {
-    input
-        .lines()
-        .map(|l| {
-            Ok(match &l[..4] {
-                "noop" => Instr::Noop,
-                "addx" => Instr::Addx(l[5..].parse()?),
-                _ => unreachable!("unknown op"),
-            })
-        })
-        .collect()
-}
-
-fn ticks(instr: &[Instr]) -> Vec<(usize, isize)> {
-    let mut acc_cycle = 0usize;
-    let mut acc_x = 1isize;
-    instr
-        .iter()
-        .map(|instr| {
-            match instr {
-                Instr::Noop => {
-                    acc_cycle += 1;
-                }
-                Instr::Addx(x) => {
-                    acc_cycle += 2;
-                    acc_x += x;
-                }
+    parse_lines(DAY, input, |l| match l.split_once(' ') {
+        None if l == "noop" => Ok(Instr::Noop),
+        Some(("addx", x)) => Ok(Instr::Addx(x.parse()?)),
+        _ => Err(anyhow!("unknown op")),
+    })
+}
+
+/// A device driven by the CPU clock, like the CRT. Closures taking the cycle
+/// and X are peripherals too, which is handy for probes.
+pub trait Peripheral {
+    /// Called during every cycle, numbered from 1, with the value of X.
+    fn tick(&mut self, cycle: usize, x: isize);
+}
+
+impl<F: FnMut(usize, isize)> Peripheral for F {
+    fn tick(&mut self, cycle: usize, x: isize) {
+        self(cycle, x)
+    }
+}
+
+/// Why [`Cpu::run`] returned.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Stop {
+    /// The program is over.
+    Halted,
+    /// The given cycle has a breakpoint and has not started yet.
+    Breakpoint(usize),
+}
+
+pub struct Cpu<'a> {
+    program: &'a [Instr],
+    pc: usize,
+    /// Cycles already spent on the instruction at `pc`.
+    busy: usize,
+    x: isize,
+    cycle: usize,
+    breakpoints: BTreeSet<usize>,
+    /// The breakpoint we stopped at, so that running again goes past it.
+    paused_at: Option<usize>,
+    peripherals: Vec<&'a mut dyn Peripheral>,
+}
+
+impl<'a> Cpu<'a> {
+    pub fn new(program: &'a [Instr]) -> Self {
+        Cpu {
+            program,
+            pc: 0,
+            busy: 0,
+            x: 1,
+            cycle: 0,
+            breakpoints: BTreeSet::new(),
+            paused_at: None,
+            peripherals: vec![],
+        }
+    }
+
+    pub fn attach(&mut self, peripheral: &'a mut dyn Peripheral) {
+        self.peripherals.push(peripheral)
+    }
+
+    pub fn add_breakpoint(&mut self, cycle: usize) {
+        self.breakpoints.insert(cycle);
+    }
+
+    pub fn x(&self) -> isize {
+        self.x
+    }
+
+    /// The number of cycles completed so far.
+    pub fn cycle(&self) -> usize {
+        self.cycle
+    }
+
+    pub fn is_halted(&self) -> bool {
+        self.pc >= self.program.len()
+    }
+
+    /// Run a single cycle. Returns false if the program was already over.
+    pub fn step(&mut self) -> bool {
+        let instr = match self.program.get(self.pc) {
+            Some(instr) => *instr,
+            None => return false,
+        };
+        self.cycle += 1;
+        for peripheral in self.peripherals.iter_mut() {
+            peripheral.tick(self.cycle, self.x);
+        }
+        self.busy += 1;
+        if self.busy == instr.cycles() {
+            if let Instr::Addx(v) = instr {
+                self.x += v;
+            }
+            self.pc += 1;
+            self.busy = 0;
+        }
+        true
+    }
+
+    /// Run until the program is over or the next cycle has a breakpoint. In
+    /// the latter case, `x()` is the value X will have during that cycle.
+    pub fn run(&mut self) -> Stop {
+        loop {
+            let next = self.cycle + 1;
+            if !self.is_halted() && self.breakpoints.contains(&next) && self.paused_at != Some(next)
+            {
+                self.paused_at = Some(next);
+                return Stop::Breakpoint(next);
+            }
+            if !self.step() {
+                return Stop::Halted;
             }
-            (acc_cycle, acc_x)
-        })
-        .collect()
-}
-
-fn find_val_at_cycle(ticks: &[(usize, isize)], idx: &usize) -> isize {
-    let idx = ticks.iter().take_while(|(cycle, _)| cycle < idx).count();
-    if idx == 0 {
-        return 1;
-    }
-    ticks[idx - 1].1
-}
-
-pub fn part1(input: Vec<Instr>) -> isize {
-    let ticks = ticks(&input);
-    [20usize, 60, 100, 140, 180, 220]
-        .into_iter()
-        .fold(0isize, |acc, idx| {
-            acc + (idx as isize) * find_val_at_cycle(&ticks, &idx)
-        })
-}
-
-pub fn part2(input: Vec<Instr>) -> char {
-    let ticks = ticks(&input);
-    println!();
-    for i in 1..=240 {
-        let x = find_val_at_cycle(&ticks, &i);
-        let pixel_pos = (i as isize - 1) % 40;
-        if (x - pixel_pos).abs() <= 1 {
-            print!("█")
-        } else {
-            print!(" ")
         }
-        if i % 40 == 0 {
-            println!()
+    }
+}
+
+/// The screen. During each cycle it draws one pixel, lit if the 3 pixels wide
+/// sprite centered on X covers it, and it starts over from the top left once
+/// every pixel has been drawn.
+pub struct Crt {
+    width: usize,
+    pixels: Vec<bool>,
+}
+
+/// The puzzle's screen is 40 pixels wide and 6 high.
+impl Default for Crt {
+    fn default() -> Self {
+        Crt::new(40, 6)
+    }
+}
+
+impl Crt {
+    /// Panics if either dimension is 0.
+    pub fn new(width: usize, height: usize) -> Self {
+        assert!(width > 0 && height > 0, "the screen cannot be empty");
+        Crt {
+            width,
+            pixels: vec![false; width * height],
         }
     }
-    ' '
+
+    pub fn bitmap(&self) -> Bitmap {
+        Bitmap::new(self.width, self.pixels.clone())
+    }
+}
+
+impl Peripheral for Crt {
+    fn tick(&mut self, cycle: usize, x: isize) {
+        let pos = (cycle - 1) % self.pixels.len();
+        let column = (pos % self.width) as isize;
+        self.pixels[pos] = (x - column).abs() <= 1;
+    }
+}
+
+pub fn part1(input: Vec<Instr>) -> Result<Answer> {
+    let mut cpu = Cpu::new(&input);
+    for cycle in (20..=220).step_by(40) {
+        cpu.add_breakpoint(cycle);
+    }
+    let mut strength = 0;
+    while let Stop::Breakpoint(cycle) = cpu.run() {
+        strength += cycle as isize * cpu.x();
+    }
+    Ok(strength.into())
+}
+
+/// What `crt` draws while running `input`.
+pub fn screen(input: &[Instr], mut crt: Crt) -> Bitmap {
+    let mut cpu = Cpu::new(input);
+    cpu.attach(&mut crt);
+    cpu.run();
+    crt.bitmap()
+}
+
+pub fn part2(input: Vec<Instr>) -> Result<Answer> {
+    let screen = screen(&input, Crt::default());
+    // show the screen as is if some letter is not in the font
+    Ok(ocr::read(&screen).map_or(screen.into(), Answer::from))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::Error;
+    use crate::render::{Format, Render};
+
+    #[test]
+    fn test_parse_unknown_op() {
+        assert_eq!(
+            parse_input("noop\naddx 3\njump 2").map(|_| ()),
+            Err(Error::parse(DAY, 3, "jump 2", "unknown op"))
+        );
+    }
+
+    #[test]
+    fn test_step() {
+        let program = [Instr::Noop, Instr::Addx(3), Instr::Addx(-5)];
+        let mut cpu = Cpu::new(&program);
+        let mut seen = vec![];
+        let mut probe = |cycle, x| seen.push((cycle, x));
+        cpu.attach(&mut probe);
+        while cpu.step() {}
+        assert_eq!((cpu.cycle(), cpu.x()), (5, -1));
+        assert_eq!(seen, vec![(1, 1), (2, 1), (3, 1), (4, 4), (5, 4)]);
+    }
+
+    #[test]
+    fn test_breakpoints() {
+        let program = [Instr::Noop, Instr::Addx(3), Instr::Addx(-5)];
+        let mut cpu = Cpu::new(&program);
+        cpu.add_breakpoint(4);
+        cpu.add_breakpoint(9);
+        assert_eq!(cpu.run(), Stop::Breakpoint(4));
+        assert_eq!((cpu.cycle(), cpu.x()), (3, 4));
+        assert_eq!(cpu.run(), Stop::Halted);
+        assert!(cpu.is_halted());
+    }
+
+    #[test]
+    fn test_crt() {
+        let program = [Instr::Addx(15), Instr::Addx(-11), Instr::Addx(6)];
+        let mut crt = Crt::default();
+        let mut cpu = Cpu::new(&program);
+        cpu.attach(&mut crt);
+        cpu.run();
+        let lit: Vec<bool> = crt.bitmap().cells()[..6].to_vec();
+        assert_eq!(lit, vec![true, true, false, false, true, true]);
+    }
+
+    #[test]
+    fn test_crt_size() {
+        // X stays at 1, so only the first 3 columns of each row are lit
+        let program = [Instr::Noop; 12];
+        let screen = screen(&program, Crt::new(6, 2));
+        assert_eq!((screen.width(), screen.height()), (6, 2));
+        assert_eq!(
+            Render(&screen, Format::Text).to_string(),
+            "###...\n###...\n"
+        );
+    }
 }
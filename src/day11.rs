@@ -1,31 +1,147 @@
-use anyhow::{anyhow, Context, Error, Result};
+use crate::answer::Answer;
+use crate::error::{parse_blocks, Error, Result};
+use anyhow::{anyhow, Context};
 use itertools::Itertools;
+use nom::branch::alt;
+use nom::bytes::complete::tag;
+use nom::character::complete::{char, digit1, multispace0, one_of};
+use nom::combinator::{all_consuming, map_res, value};
+use nom::multi::fold_many0;
+use nom::sequence::{delimited, pair};
+use nom::IResult;
+use num::{BigUint, CheckedAdd, CheckedDiv, CheckedMul, CheckedSub};
+use std::collections::HashMap;
+use std::fmt;
+use std::hash::Hash;
+use std::ops::Rem;
 use std::str::FromStr;
 
+const DAY: u8 = 11;
+
+/// The right-hand side of an operation, `new = ...`.
 #[derive(Debug, PartialEq, Clone)]
-enum Op {
-    Mul(usize),
-    Sum(usize),
-    Square,
+enum Expr {
+    Old,
+    Num(usize),
+    Add(Box<Expr>, Box<Expr>),
+    Sub(Box<Expr>, Box<Expr>),
+    Mul(Box<Expr>, Box<Expr>),
+    Div(Box<Expr>, Box<Expr>),
 }
 
-impl FromStr for Op {
-    type Err = Error;
+impl Expr {
+    /// The value for the worry level `old`, or `None` if a step overflows,
+    /// goes below zero or divides by zero.
+    fn eval<W: Worry>(&self, old: &W) -> Option<W> {
+        match self {
+            Expr::Old => Some(old.clone()),
+            Expr::Num(n) => Some(W::from(*n)),
+            Expr::Add(a, b) => a.eval(old)?.checked_add(&b.eval(old)?),
+            Expr::Sub(a, b) => a.eval(old)?.checked_sub(&b.eval(old)?),
+            Expr::Mul(a, b) => a.eval(old)?.checked_mul(&b.eval(old)?),
+            Expr::Div(a, b) => a.eval(old)?.checked_div(&b.eval(old)?),
+        }
+    }
+
+    /// Whether only `+` and `*` are used, so that the value modulo any number
+    /// only depends on `old` modulo that number.
+    fn is_modular(&self) -> bool {
+        match self {
+            Expr::Old | Expr::Num(_) => true,
+            Expr::Add(a, b) | Expr::Mul(a, b) => a.is_modular() && b.is_modular(),
+            Expr::Sub(..) | Expr::Div(..) => false,
+        }
+    }
 
-    fn from_str(op: &str) -> Result<Self> {
-        if op.contains('*') {
-            let (_, qty) = op.split_once("* ").context("no")?;
-            if qty == "old" {
-                Ok(Op::Square)
+    /// Operands and how tightly the operator binds, for binary expressions.
+    fn binary(&self) -> Option<(&Expr, char, &Expr, u8)> {
+        match self {
+            Expr::Old | Expr::Num(_) => None,
+            Expr::Add(a, b) => Some((a, '+', b, 1)),
+            Expr::Sub(a, b) => Some((a, '-', b, 1)),
+            Expr::Mul(a, b) => Some((a, '*', b, 2)),
+            Expr::Div(a, b) => Some((a, '/', b, 2)),
+        }
+    }
+}
+
+/// Writes the expression with as few parentheses as needed.
+impl fmt::Display for Expr {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Expr::Old => return write!(f, "old"),
+            Expr::Num(n) => return write!(f, "{}", n),
+            _ => (),
+        }
+        let (a, op, b, precedence) = self.binary().expect("a binary expression");
+        // operators are left associative, so only the right operand needs
+        // parentheses for the same precedence
+        let left = matches!(a.binary(), Some((.., p)) if p < precedence);
+        let right = matches!(b.binary(), Some((.., p)) if p <= precedence);
+        let wrap = |e: &Expr, paren: bool| {
+            if paren {
+                format!("({})", e)
             } else {
-                Ok(Op::Mul(qty.parse()?))
+                e.to_string()
             }
-        } else if op.contains('+') {
-            let (_, qty) = op.split_once("+ ").context("nope")?;
-            Ok(Op::Sum(qty.parse()?))
-        } else {
-            Err(anyhow!("unknown op {}", &op))
-        }
+        };
+        write!(f, "{} {} {}", wrap(a, left), op, wrap(b, right))
+    }
+}
+
+fn ws<'a, O>(
+    inner: impl FnMut(&'a str) -> IResult<&'a str, O>,
+) -> impl FnMut(&'a str) -> IResult<&'a str, O> {
+    delimited(multispace0, inner, multispace0)
+}
+
+fn atom(s: &str) -> IResult<&str, Expr> {
+    alt((
+        value(Expr::Old, tag("old")),
+        map_res(digit1, |n: &str| n.parse().map(Expr::Num)),
+        delimited(char('('), ws(expr), char(')')),
+    ))(s)
+}
+
+/// A sequence of `operand` joined by the operators in `ops`, from the left.
+fn chain<'a>(
+    s: &'a str,
+    operand: fn(&'a str) -> IResult<&'a str, Expr>,
+    ops: &'static str,
+) -> IResult<&'a str, Expr> {
+    let (s, first) = operand(s)?;
+    fold_many0(
+        pair(ws(one_of(ops)), operand),
+        move || first.clone(),
+        |a, (op, b)| {
+            let (a, b) = (Box::new(a), Box::new(b));
+            match op {
+                '+' => Expr::Add(a, b),
+                '-' => Expr::Sub(a, b),
+                '*' => Expr::Mul(a, b),
+                _ => Expr::Div(a, b),
+            }
+        },
+    )(s)
+}
+
+fn term(s: &str) -> IResult<&str, Expr> {
+    chain(s, atom, "*/")
+}
+
+fn expr(s: &str) -> IResult<&str, Expr> {
+    chain(s, term, "+-")
+}
+
+impl FromStr for Expr {
+    type Err = anyhow::Error;
+
+    /// Parse an operation line like `Operation: new = old * (old + 3)`.
+    fn from_str(op: &str) -> anyhow::Result<Self> {
+        let (_, rhs) = op.split_once("new =").context("no 'new ='")?;
+        all_consuming(ws(expr))(rhs)
+            .map(|(_, e)| e)
+            .map_err(|e| anyhow!("invalid operation: {}", e))
     }
 }
 
@@ -37,9 +153,9 @@ struct Test {
 }
 
 impl FromStr for Test {
-    type Err = Error;
+    type Err = anyhow::Error;
 
-    fn from_str(test: &str) -> Result<Test> {
+    fn from_str(test: &str) -> anyhow::Result<Test> {
         let mut lines = test.lines();
         let div: usize = lines
             .next()
@@ -48,6 +164,9 @@ impl FromStr for Test {
             .next()
             .context("no space")?
             .parse()?;
+        if div == 0 {
+            return Err(anyhow!("cannot test divisibility by 0"));
+        }
         let yes: usize = lines
             .next()
             .context("empty")?
@@ -70,15 +189,14 @@ impl FromStr for Test {
 pub struct Monkey {
     id: usize,
     items: Vec<usize>,
-    op: Op,
+    op: Expr,
     test: Test,
-    throw_count: usize,
 }
 
 impl FromStr for Monkey {
-    type Err = Error;
+    type Err = anyhow::Error;
 
-    fn from_str(monkey: &str) -> Result<Monkey> {
+    fn from_str(monkey: &str) -> anyhow::Result<Monkey> {
         let mut lines = monkey.lines();
         let mut chars = lines
             .next()
@@ -97,9 +215,9 @@ impl FromStr for Monkey {
 
         let items: Vec<usize> = items
             .split(", ")
-            .map(|i| i.trim().parse().unwrap())
-            .collect();
-        let op: Op = lines.next().context("empty")?.parse()?;
+            .map(|i| i.trim().parse())
+            .collect::<std::result::Result<_, _>>()?;
+        let op: Expr = lines.next().context("empty")?.parse()?;
 
         let rest = format!("{}", lines.format("\n"));
         let test: Test = rest.parse()?;
@@ -108,114 +226,463 @@ impl FromStr for Monkey {
             items,
             op,
             test,
-            throw_count: 0,
         })
     }
 }
 
 pub fn parse_input(input: &str) -> Result<Vec<Monkey>> {
-    input.split("\n\n").map(|m| m.parse()).collect()
-}
-
-fn process(monkey: &mut Monkey) -> Vec<(usize, usize)> {
-    let mut moves = Vec::default();
-    for item in &monkey.items {
-        let mut item = *item;
-        match monkey.op {
-            Op::Sum(qty) => item += qty,
-            Op::Mul(qty) => item *= qty,
-            Op::Square => item *= item,
-        };
-        item /= 3;
-        if item % monkey.test.div == 0 {
-            moves.push((monkey.test.yes, item));
-        } else {
-            moves.push((monkey.test.no, item))
+    let monkeys = parse_blocks(DAY, input, |m| m.parse::<Monkey>())?;
+    for (i, m) in monkeys.iter().enumerate() {
+        if m.id != i {
+            return Err(Error::input(
+                DAY,
+                format!("monkey {} is at position {}", m.id, i),
+            ));
+        }
+        if m.test.yes >= monkeys.len() || m.test.no >= monkeys.len() {
+            return Err(Error::input(
+                DAY,
+                format!("monkey {} throws to an unknown monkey", i),
+            ));
         }
     }
-    moves
+    Ok(monkeys)
 }
 
-fn throw(monkeys: &mut [Monkey], moves: Vec<(usize, usize)>, monkey_id: usize) {
-    for (id, item) in moves {
-        monkeys[id].items.push(item)
+/// A worry level, `usize` or a big integer for instance.
+pub trait Worry:
+    Clone
+    + PartialEq
+    + fmt::Display
+    + From<usize>
+    + CheckedAdd
+    + CheckedSub
+    + CheckedMul
+    + CheckedDiv
+    + Rem<Output = Self>
+{
+}
+
+impl<T> Worry for T where
+    T: Clone
+        + PartialEq
+        + fmt::Display
+        + From<usize>
+        + CheckedAdd
+        + CheckedSub
+        + CheckedMul
+        + CheckedDiv
+        + Rem<Output = T>
+{
+}
+
+/// How worry levels are kept manageable after each inspection.
+pub trait WorryPolicy {
+    type Worry: Worry;
+
+    fn relieve(&self, worry: Self::Worry) -> Self::Worry;
+}
+
+/// The relief of part 1, when an item is not damaged.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct DivideBy3;
+
+impl WorryPolicy for DivideBy3 {
+    type Worry = usize;
+
+    fn relieve(&self, worry: usize) -> usize {
+        worry / 3
     }
-    let len: usize = monkeys[monkey_id].items.len();
-    monkeys[monkey_id].throw_count += len;
-    monkeys[monkey_id].items.clear();
 }
 
-fn round(monkeys: &mut Vec<Monkey>) {
-    for i in 0..monkeys.len() {
-        let moves = process(&mut monkeys[i]);
-        throw(monkeys, moves, i);
+/// Keeps the worry levels modulo the product of the divisors of the tests,
+/// which changes no test result as long as the operations only add and
+/// multiply.
+#[derive(Debug, Clone, Copy)]
+pub struct ModProduct(pub usize);
+
+impl ModProduct {
+    /// Fails if an operation subtracts or divides, as the result modulo the
+    /// product would then depend on more than the reduced worry level.
+    pub fn new(monkeys: &[Monkey]) -> Result<Self> {
+        if let Some(m) = monkeys.iter().find(|m| !m.op.is_modular()) {
+            return Err(Error::input(
+                DAY,
+                format!(
+                    "monkey {} computes {}, which cannot be reduced modulo a product",
+                    m.id, m.op
+                ),
+            ));
+        }
+        Ok(ModProduct(monkeys.iter().map(|m| m.test.div).product()))
     }
 }
 
-fn compute_monkey_business(monkeys: Vec<Monkey>) -> usize {
-    let mut throws = Vec::default();
-    for monkey in monkeys {
-        throws.push(monkey.throw_count);
+impl WorryPolicy for ModProduct {
+    type Worry = usize;
+
+    fn relieve(&self, worry: usize) -> usize {
+        worry % self.0
     }
-    throws.sort();
-    throws[throws.len() - 1] * throws[throws.len() - 2]
 }
-pub fn part1(mut monkeys: Vec<Monkey>) -> usize {
-    for _ in 0..20 {
-        round(&mut monkeys);
+
+/// No relief at all. The worry levels grow without bound, squaring ones
+/// doubling in length, so this is only practical for a few rounds.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Unreduced;
+
+impl WorryPolicy for Unreduced {
+    type Worry = BigUint;
+
+    fn relieve(&self, worry: BigUint) -> BigUint {
+        worry
     }
-    compute_monkey_business(monkeys)
 }
 
-fn process_p2(monkey: &mut Monkey, constant: &usize) -> Vec<(usize, usize)> {
-    let mut moves = Vec::default();
-    for item in &monkey.items {
-        let mut item = *item;
-        match monkey.op {
-            Op::Sum(qty) => item += qty,
-            Op::Mul(qty) => item *= qty,
-            Op::Square => item = item.pow(2),
-        };
-        item %= constant;
-        if item % monkey.test.div == 0 {
-            moves.push((monkey.test.yes, item));
+/// Monkeys playing with the items, the worry levels being managed by `P`.
+#[derive(Debug, Clone)]
+pub struct MonkeyTroop<P: WorryPolicy> {
+    monkeys: Vec<Monkey>,
+    policy: P,
+    /// The worry levels of the items each monkey holds.
+    items: Vec<Vec<P::Worry>>,
+    /// How many items each monkey has inspected.
+    throws: Vec<usize>,
+}
+
+impl<P: WorryPolicy> MonkeyTroop<P> {
+    /// Start with the items the monkeys are described with.
+    pub fn new(monkeys: Vec<Monkey>, policy: P) -> Self {
+        let items = monkeys
+            .iter()
+            .map(|m| m.items.iter().map(|&i| P::Worry::from(i)).collect())
+            .collect();
+        let throws = vec![0; monkeys.len()];
+        MonkeyTroop {
+            monkeys,
+            policy,
+            items,
+            throws,
+        }
+    }
+
+    pub fn items(&self, monkey: usize) -> &[P::Worry] {
+        &self.items[monkey]
+    }
+
+    pub fn throws(&self) -> &[usize] {
+        &self.throws
+    }
+
+    /// The new worry level of an item inspected by `monkey`, and where the
+    /// monkey throws it. Fails if the operation of the monkey cannot be
+    /// computed for `worry`.
+    pub fn inspect(&self, monkey: usize, worry: P::Worry) -> Result<(usize, P::Worry)> {
+        let Monkey { op, test, .. } = &self.monkeys[monkey];
+        let new = op.eval(&worry).ok_or_else(|| {
+            Error::no_solution(
+                DAY,
+                format!(
+                    "monkey {} cannot compute {} for old = {}",
+                    monkey, op, worry
+                ),
+            )
+        })?;
+        let worry = self.policy.relieve(new);
+        if worry.clone() % P::Worry::from(test.div) == P::Worry::from(0) {
+            Ok((test.yes, worry))
         } else {
-            moves.push((monkey.test.no, item));
+            Ok((test.no, worry))
+        }
+    }
+
+    /// Every monkey in turn inspects and throws all its items. On error, the
+    /// round is left unfinished.
+    pub fn round(&mut self) -> Result<()> {
+        for i in 0..self.monkeys.len() {
+            let items = std::mem::take(&mut self.items[i]);
+            self.throws[i] += items.len();
+            for worry in items {
+                let (target, worry) = self.inspect(i, worry)?;
+                self.items[target].push(worry);
+            }
+        }
+        Ok(())
+    }
+
+    pub fn rounds(&mut self, rounds: usize) -> Result<()> {
+        for _ in 0..rounds {
+            self.round()?;
+        }
+        Ok(())
+    }
+
+    /// Follow one item through a round, starting with `monkey`. Returns the
+    /// monkeys inspecting it, and the monkey and worry level it ends with.
+    fn item_round(
+        &self,
+        mut monkey: usize,
+        mut worry: P::Worry,
+    ) -> Result<(Vec<usize>, usize, P::Worry)> {
+        let mut inspections = vec![];
+        loop {
+            inspections.push(monkey);
+            let (target, new) = self.inspect(monkey, worry)?;
+            worry = new;
+            // thrown to a monkey that already played, it waits for the next round
+            if target <= monkey {
+                return Ok((inspections, target, worry));
+            }
+            monkey = target;
+        }
+    }
+
+    /// The monkeys inspecting an item in each of `rounds` rounds, the item
+    /// being with `monkey` at the start. Items never interact, so one can be
+    /// followed on its own.
+    pub fn trace(
+        &self,
+        mut monkey: usize,
+        mut worry: P::Worry,
+        rounds: usize,
+    ) -> Result<Vec<Vec<usize>>> {
+        let mut trace = vec![];
+        for _ in 0..rounds {
+            let (inspections, next, new) = self.item_round(monkey, worry)?;
+            trace.push(inspections);
+            monkey = next;
+            worry = new;
+        }
+        Ok(trace)
+    }
+
+    /// Follow an item until it is back with the same monkey and worry level
+    /// at the start of a round, from which on its journey repeats. Fails if
+    /// that takes more than `limit` rounds.
+    pub fn item_cycle(
+        &self,
+        mut monkey: usize,
+        mut worry: P::Worry,
+        limit: usize,
+    ) -> Result<ItemCycle>
+    where
+        P::Worry: Hash + Eq,
+    {
+        let mut seen = HashMap::new();
+        let mut rounds = vec![];
+        while rounds.len() <= limit {
+            if let Some(&start) = seen.get(&(monkey, worry.clone())) {
+                return Ok(ItemCycle { rounds, start });
+            }
+            seen.insert((monkey, worry.clone()), rounds.len());
+            let (inspections, next, new) = self.item_round(monkey, worry)?;
+            rounds.push(inspections);
+            monkey = next;
+            worry = new;
+        }
+        Err(Error::no_solution(
+            DAY,
+            format!("an item does not repeat within {} rounds", limit),
+        ))
+    }
+
+    /// How many items each monkey inspects in the next `rounds` rounds,
+    /// computed from the cycle of every item rather than by playing them.
+    pub fn throws_after(&self, rounds: u128, limit: usize) -> Result<Vec<u128>>
+    where
+        P::Worry: Hash + Eq,
+    {
+        let mut throws = vec![0; self.monkeys.len()];
+        for (monkey, items) in self.items.iter().enumerate() {
+            for worry in items {
+                let cycle = self.item_cycle(monkey, worry.clone(), limit)?;
+                for (total, n) in throws
+                    .iter_mut()
+                    .zip(cycle.throws(self.monkeys.len(), rounds))
+                {
+                    *total += n;
+                }
+            }
+        }
+        Ok(throws)
+    }
+
+    /// The product of the two largest throw counts.
+    pub fn monkey_business(&self) -> Result<usize> {
+        let mut throws = self.throws.clone();
+        throws.sort();
+        match throws[..] {
+            [.., a, b] => Ok(a * b),
+            _ => Err(Error::no_solution(DAY, "less than 2 monkeys")),
         }
     }
-    moves
 }
-fn round_p2(monkeys: &mut Vec<Monkey>, constant: &usize) {
-    for i in 0..monkeys.len() {
-        let moves = process_p2(&mut monkeys[i], constant);
-        throw(monkeys, moves, i);
+
+/// The journey of an item, round by round, until it is in a state it was in
+/// before.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ItemCycle {
+    /// The monkeys inspecting the item in each round.
+    pub rounds: Vec<Vec<usize>>,
+    /// The round from which `rounds` repeats forever.
+    pub start: usize,
+}
+
+impl ItemCycle {
+    /// The number of rounds after which the journey repeats.
+    pub fn period(&self) -> usize {
+        self.rounds.len() - self.start
+    }
+
+    /// How many times each of `monkeys` monkeys inspects the item in the
+    /// first `rounds` rounds.
+    pub fn throws(&self, monkeys: usize, rounds: u128) -> Vec<u128> {
+        let mut throws = vec![0; monkeys];
+        let mut add = |range: &[Vec<usize>], times: u128| {
+            for &m in range.iter().flatten() {
+                throws[m] += times;
+            }
+        };
+        let prefix = rounds.min(self.start as u128) as usize;
+        add(&self.rounds[..prefix], 1);
+        if let Some(rest) = rounds.checked_sub(self.start as u128) {
+            let period = self.period() as u128;
+            add(&self.rounds[self.start..], rest / period);
+            let partial = (rest % period) as usize;
+            add(&self.rounds[self.start..self.start + partial], 1);
+        }
+        throws
     }
 }
 
-pub fn part2(mut monkeys: Vec<Monkey>) -> usize {
-    let constant = monkeys.iter().map(|x| x.test.div).product();
-    for _ in 0..10_000 {
-        round_p2(&mut monkeys, &constant);
+/// Play `rounds` rounds with exact worry levels and with worry levels modulo
+/// the product of the divisors, side by side. Returns the first round after
+/// which the throw counts, or the worry levels modulo that product, differ.
+pub fn diverging_round(monkeys: &[Monkey], rounds: usize) -> Result<Option<usize>> {
+    let policy = ModProduct::new(monkeys)?;
+    let modulus = BigUint::from(policy.0);
+    let mut exact = MonkeyTroop::new(monkeys.to_vec(), Unreduced);
+    let mut reduced = MonkeyTroop::new(monkeys.to_vec(), policy);
+    for round in 1..=rounds {
+        exact.round()?;
+        reduced.round()?;
+        let same_items = (0..monkeys.len()).all(|m| {
+            exact
+                .items(m)
+                .iter()
+                .map(|w| w % &modulus)
+                .eq(reduced.items(m).iter().map(|&w| BigUint::from(w)))
+        });
+        if exact.throws() != reduced.throws() || !same_items {
+            return Ok(Some(round));
+        }
     }
-    compute_monkey_business(monkeys)
+    Ok(None)
+}
+
+pub fn part1(monkeys: Vec<Monkey>) -> Result<Answer> {
+    let mut troop = MonkeyTroop::new(monkeys, DivideBy3);
+    troop.rounds(20)?;
+    troop.monkey_business().map(Answer::from)
+}
+
+pub fn part2(monkeys: Vec<Monkey>) -> Result<Answer> {
+    let policy = ModProduct::new(&monkeys)?;
+    let mut troop = MonkeyTroop::new(monkeys, policy);
+    troop.rounds(10_000)?;
+    troop.monkey_business().map(Answer::from)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
     use anyhow::Result;
+    use num::integer::lcm;
+
+    const EXAMPLE: &str = "Monkey 0:
+Starting items: 79, 98
+Operation: new = old * 19
+Test: divisible by 23
+    If true: throw to monkey 2
+    If false: throw to monkey 3
+
+Monkey 1:
+Starting items: 54, 65, 75, 74
+Operation: new = old + 6
+Test: divisible by 19
+    If true: throw to monkey 2
+    If false: throw to monkey 0
+
+Monkey 2:
+Starting items: 79, 60, 97
+Operation: new = old * old
+Test: divisible by 13
+    If true: throw to monkey 1
+    If false: throw to monkey 3
+
+Monkey 3:
+Starting items: 74
+Operation: new = old + 3
+Test: divisible by 17
+    If true: throw to monkey 0
+    If false: throw to monkey 1";
+
+    fn parse_op(op: &str) -> Expr {
+        op.parse().unwrap()
+    }
 
     #[test]
     fn test_op_parse() {
-        if let Ok(op) = "  Operation: new = old + 3".parse::<Op>() {
-            assert_eq!(op, Op::Sum(3));
-        }
-        if let Ok(op) = "  Operation: new = old * 39".parse::<Op>() {
-            assert_eq!(op, Op::Mul(39));
-        }
-        if let Ok(op) = "  Operation: new = old * old".parse::<Op>() {
-            assert_eq!(op, Op::Square);
+        let (old, num) = (|| Box::new(Expr::Old), |n| Box::new(Expr::Num(n)));
+        assert_eq!(
+            parse_op("  Operation: new = old + 3"),
+            Expr::Add(old(), num(3))
+        );
+        assert_eq!(
+            parse_op("  Operation: new = old * 39"),
+            Expr::Mul(old(), num(39))
+        );
+        assert_eq!(
+            parse_op("  Operation: new = old * old"),
+            Expr::Mul(old(), old())
+        );
+        assert_eq!(parse_op("new = 3 - old"), Expr::Sub(num(3), old()));
+        assert_eq!(
+            parse_op("new = old + 2 * (old - 1) / 4"),
+            Expr::Add(
+                old(),
+                Box::new(Expr::Div(
+                    Box::new(Expr::Mul(num(2), Box::new(Expr::Sub(old(), num(1))))),
+                    num(4)
+                ))
+            )
+        );
+        assert!("new = old +".parse::<Expr>().is_err());
+        assert!("new = (old".parse::<Expr>().is_err());
+        assert!("old * 2".parse::<Expr>().is_err());
+    }
+
+    #[test]
+    fn test_op_display_and_eval() {
+        for (op, shown, value) in [
+            ("new = old + old", "old + old", Some(14)),
+            (
+                "new = (old - 1) * (old + 1)",
+                "(old - 1) * (old + 1)",
+                Some(48),
+            ),
+            ("new = old - (3 - 1)", "old - (3 - 1)", Some(5)),
+            ("new = (old - 3) - 1", "old - 3 - 1", Some(3)),
+            ("new = old / 2 / 2", "old / 2 / 2", Some(1)),
+            ("new = old - 8", "old - 8", None),
+            ("new = old / (old - 7)", "old / (old - 7)", None),
+        ] {
+            let op = parse_op(op);
+            assert_eq!(op.to_string(), shown);
+            assert_eq!(op.eval(&7usize), value, "{}", shown);
+            assert_eq!(parse_op(&format!("new = {}", shown)), op);
         }
+        assert_eq!(parse_op("new = old * old").eval(&usize::MAX), None);
     }
 
     #[test]
@@ -249,13 +716,12 @@ mod tests {
             Monkey {
                 id: 0,
                 items: vec![79, 98],
-                op: Op::Mul(19),
+                op: Expr::Mul(Box::new(Expr::Old), Box::new(Expr::Num(19))),
                 test: Test {
                     div: 23,
                     yes: 2,
                     no: 3
                 },
-                throw_count: 0,
             }
         );
         Ok(())
@@ -263,84 +729,143 @@ mod tests {
 
     #[test]
     fn test_process() -> Result<()> {
-        let mut m: Monkey = "Monkey 0:
+        let m: Monkey = "Monkey 0:
   Starting items: 79, 98
   Operation: new = old * 19
   Test: divisible by 23
     If true: throw to monkey 2
     If false: throw to monkey 3"
             .parse()?;
-        assert_eq!(process(&mut m), vec![(3, 500), (3, 620)]);
+        let troop = MonkeyTroop::new(vec![m], DivideBy3);
+        assert_eq!(troop.inspect(0, 79)?, (3, 500));
+        assert_eq!(troop.inspect(0, 98)?, (3, 620));
         Ok(())
     }
 
     #[test]
     fn test_part1() {
-        let input = "Monkey 0:
-Starting items: 79, 98
-Operation: new = old * 19
-Test: divisible by 23
-    If true: throw to monkey 2
-    If false: throw to monkey 3
-
-Monkey 1:
-Starting items: 54, 65, 75, 74
-Operation: new = old + 6
-Test: divisible by 19
-    If true: throw to monkey 2
-    If false: throw to monkey 0
-
-Monkey 2:
-Starting items: 79, 60, 97
-Operation: new = old * old
-Test: divisible by 13
-    If true: throw to monkey 1
-    If false: throw to monkey 3
-
-Monkey 3:
-Starting items: 74
-Operation: new = old + 3
-Test: divisible by 17
-    If true: throw to monkey 0
-    If false: throw to monkey 1";
+        let input = EXAMPLE;
         if let Ok(monkeys) = parse_input(input) {
             dbg!(&monkeys);
-            assert_eq!(part1(monkeys), 10605);
+            assert_eq!(part1(monkeys), Ok(10605.into()));
         }
     }
 
     #[test]
     fn test_part2() {
-        let input = "Monkey 0:
-Starting items: 79, 98
-Operation: new = old * 19
-Test: divisible by 23
-    If true: throw to monkey 2
-    If false: throw to monkey 3
+        let input = EXAMPLE;
+        if let Ok(monkeys) = parse_input(input) {
+            dbg!(&monkeys);
+            assert_eq!(part2(monkeys), Ok(2713310158usize.into()));
+        }
+    }
 
-Monkey 1:
-Starting items: 54, 65, 75, 74
-Operation: new = old + 6
-Test: divisible by 19
-    If true: throw to monkey 2
-    If false: throw to monkey 0
+    /// A policy defined outside of the troop.
+    struct ModLcm(usize);
 
-Monkey 2:
-Starting items: 79, 60, 97
-Operation: new = old * old
-Test: divisible by 13
-    If true: throw to monkey 1
-    If false: throw to monkey 3
+    impl WorryPolicy for ModLcm {
+        type Worry = usize;
 
-Monkey 3:
-Starting items: 74
-Operation: new = old + 3
-Test: divisible by 17
-    If true: throw to monkey 0
-    If false: throw to monkey 1";
-        if let Ok(monkeys) = parse_input(input) {
-            dbg!(&monkeys);
-            assert_eq!(part2(monkeys), 2713310158);
+        fn relieve(&self, worry: usize) -> usize {
+            worry % self.0
         }
     }
+
+    #[test]
+    fn test_troop() -> Result<()> {
+        let monkeys = parse_input(EXAMPLE)?;
+        let mut troop = MonkeyTroop::new(monkeys.clone(), DivideBy3);
+        troop.round()?;
+        assert_eq!(troop.items(0), [20, 23, 27, 26]);
+        assert_eq!(troop.items(1), [2080, 25, 167, 207, 401, 1046]);
+        assert!(troop.items(2).is_empty());
+        troop.rounds(19)?;
+        assert_eq!(troop.throws(), [101, 95, 7, 105]);
+
+        let modulus = monkeys.iter().map(|m| m.test.div).fold(1, lcm);
+        let mut troop = MonkeyTroop::new(monkeys, ModLcm(modulus));
+        troop.rounds(1000)?;
+        assert_eq!(troop.throws(), [5204, 4792, 199, 5192]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_unreduced() -> Result<()> {
+        let monkeys = parse_input(EXAMPLE)?;
+        let mut troop = MonkeyTroop::new(monkeys.clone(), Unreduced);
+        troop.rounds(20)?;
+        // the same as with `ModProduct`, given in the puzzle text
+        assert_eq!(troop.throws(), [99, 97, 8, 103]);
+        assert!(troop.items(0).iter().any(|w| w.bits() > 64));
+        assert_eq!(diverging_round(&monkeys, 20)?, None);
+        Ok(())
+    }
+
+    #[test]
+    fn test_custom_operations() -> Result<()> {
+        let mut monkeys = parse_input(EXAMPLE)?;
+        monkeys[3].op = parse_op("new = old - 80");
+        let mut troop = MonkeyTroop::new(monkeys.clone(), DivideBy3);
+        assert_eq!(
+            troop.rounds(20).unwrap_err().to_string(),
+            "day 11: no solution: monkey 3 cannot compute old - 80 for old = 74"
+        );
+        for op in ["new = old - 80", "new = (old + 1) / 2"] {
+            monkeys[3].op = parse_op(op);
+            assert_eq!(
+                ModProduct::new(&monkeys).unwrap_err().to_string(),
+                format!(
+                    "day 11: invalid input: monkey 3 computes {}, which cannot be reduced modulo a product",
+                    &op[6..]
+                )
+            );
+            assert!(part2(monkeys.clone()).is_err());
+        }
+        monkeys[3].op = parse_op("new = (old + 3) * old");
+        assert!(ModProduct::new(&monkeys).is_ok());
+        Ok(())
+    }
+
+    #[test]
+    fn test_trace() -> Result<()> {
+        let monkeys = parse_input(EXAMPLE)?;
+        let troop = MonkeyTroop::new(monkeys, DivideBy3);
+        // 79 goes to monkey 3 with 500, then to monkey 1 with 167, which is
+        // only played in the next round
+        let trace = troop.trace(0, 79, 2)?;
+        assert_eq!(trace, [vec![0, 3], vec![1, 2, 3]]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_item_cycle() -> Result<()> {
+        let monkeys = parse_input(EXAMPLE)?;
+        let policy = ModProduct::new(&monkeys)?;
+        let mut troop = MonkeyTroop::new(monkeys, policy);
+        let cycle = troop.item_cycle(0, 79, 1_000_000)?;
+        assert!(cycle.period() > 0);
+        // the journey repeats exactly
+        let trace = troop.trace(0, 79, cycle.rounds.len() + cycle.period())?;
+        assert_eq!(trace[..cycle.rounds.len()], cycle.rounds[..]);
+        assert_eq!(trace[cycle.rounds.len()..], cycle.rounds[cycle.start..]);
+        assert!(troop.item_cycle(0, 79, 1).is_err());
+
+        // counting throws from the cycles is the same as playing the rounds
+        for rounds in [0, 1, 20, 1000, 10_000] {
+            let mut played = troop.clone();
+            played.rounds(rounds)?;
+            let played: Vec<u128> = played.throws().iter().map(|&t| t as u128).collect();
+            assert_eq!(troop.throws_after(rounds as u128, 1_000_000)?, played);
+        }
+        assert_eq!(
+            troop.throws_after(10_000, 1_000_000)?,
+            [52166, 47830, 1938, 52013]
+        );
+        // and can go much further
+        let throws = troop.throws_after(10u128.pow(30), 1_000_000)?;
+        assert!(throws.iter().all(|&t| t > 10u128.pow(29)));
+        troop.round()?;
+        assert_eq!(troop.throws_after(0, 1_000_000)?, [0, 0, 0, 0]);
+        Ok(())
+    }
 }
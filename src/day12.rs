@@ -1,7 +1,10 @@
-use anyhow::{Error, Result};
+use crate::answer::Answer;
+use crate::error::{Error, Result};
 use pathfinding::prelude::{bfs, Matrix};
 use std::str::FromStr;
 
+const DAY: u8 = 12;
+
 #[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
 struct Pos(usize, usize);
 
@@ -16,35 +19,30 @@ impl FromStr for Map {
     type Err = Error;
 
     fn from_str(grid: &str) -> Result<Map> {
-        let start = grid
-            .lines()
-            .enumerate()
-            .find_map(|(i, r)| {
-                r.chars()
-                    .enumerate()
-                    .find_map(|(j, c)| if c == 'S' { Some(j) } else { None })
-                    .map(|j| Pos(i, j))
-            })
-            .unwrap();
-        let goal = grid
+        let find = |marker| {
+            grid.lines()
+                .enumerate()
+                .find_map(|(i, r)| r.chars().position(|c| c == marker).map(|j| Pos(i, j)))
+                .ok_or_else(|| Error::input(DAY, format!("no {} in the map", marker)))
+        };
+        let start = find('S')?;
+        let goal = find('E')?;
+
+        let rows = grid
             .lines()
             .enumerate()
-            .find_map(|(i, r)| {
-                r.chars()
-                    .enumerate()
-                    .find_map(|(j, c)| if c == 'E' { Some(j) } else { None })
-                    .map(|j| Pos(i, j))
+            .map(|(i, row)| {
+                row.chars()
+                    .map(|c| match c {
+                        'a'..='z' => Ok(c as i8 - 'a' as i8),
+                        'S' => Ok(0),
+                        'E' => Ok(25),
+                        _ => Err(Error::parse(DAY, i + 1, row, format!("unknown char {}", c))),
+                    })
+                    .collect::<Result<Vec<_>>>()
             })
-            .unwrap();
-
-        let map = Matrix::from_rows(grid.lines().map(|row| {
-            row.chars().map(|c| match c {
-                'a'..='z' => c as i8 - 'a' as i8,
-                'S' => 0,
-                'E' => 25,
-                _ => unreachable!("unknown char found"),
-            })
-        }))?;
+            .collect::<Result<Vec<_>>>()?;
+        let map = Matrix::from_rows(rows).map_err(|e| Error::input(DAY, e))?;
         Ok(Map { map, start, goal })
     }
 }
@@ -69,12 +67,13 @@ pub fn parse_input(input: &str) -> Result<Map> {
     input.parse()
 }
 
-pub fn part1(map: Map) -> isize {
+pub fn part1(map: Map) -> Result<Answer> {
     let result = bfs(&map.start, |p| map.successors(p), |p| *p == map.goal);
-    result.unwrap().len() as isize - 1
+    let path = result.ok_or_else(|| Error::no_solution(DAY, "no path from S to E"))?;
+    Ok((path.len() - 1).into())
 }
 
-pub fn part2(map: Map) -> isize {
+pub fn part2(map: Map) -> Result<Answer> {
     let mut starts = vec![];
     for i in 0..map.map.rows {
         for j in 0..map.map.columns {
@@ -83,7 +82,7 @@ pub fn part2(map: Map) -> isize {
             }
         }
     }
-    bfs(
+    let path = bfs(
         &map.goal,
         |&Pos(i, j)| {
             let current_val = map.map[(i, j)];
@@ -100,9 +99,8 @@ pub fn part2(map: Map) -> isize {
         },
         |&Pos(i, j)| map.map[(i, j)] == 0,
     )
-    .unwrap()
-    .len() as isize
-        - 1
+    .ok_or_else(|| Error::no_solution(DAY, "no path from E to an a"))?;
+    Ok((path.len() - 1).into())
 }
 
 #[cfg(test)]
@@ -126,6 +124,18 @@ cdE";
         }
     }
 
+    #[test]
+    fn test_map_parse_errors() {
+        assert_eq!(
+            "abc\ncdE".parse::<Map>(),
+            Err(Error::input(DAY, "no S in the map"))
+        );
+        assert_eq!(
+            "aSb\nc?E".parse::<Map>(),
+            Err(Error::parse(DAY, 2, "c?E", "unknown char ?"))
+        );
+    }
+
     #[test]
     fn test_map_successors() {
         let input = "Sabqponm
@@ -143,7 +153,7 @@ abdefghi";
     fn test_part1() {
         let input = "SabcdefghijklmnopqrstuvwxyzE";
         if let Ok(map) = parse_input(input) {
-            assert_eq!(part1(map), 27);
+            assert_eq!(part1(map), Ok(27.into()));
         }
     }
 
@@ -155,7 +165,7 @@ accszExk
 acctuvwj
 abdefghi";
         if let Ok(map) = parse_input(input) {
-            assert_eq!(part2(map), 29);
+            assert_eq!(part2(map), Ok(29.into()));
         }
     }
 }
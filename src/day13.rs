@@ -1,6 +1,8 @@
 use std::cmp::Ordering;
 
-use anyhow::{anyhow, Result};
+use crate::answer::Answer;
+use crate::error::{parse_blocks, Error, Result};
+use anyhow::anyhow;
 use itertools::Itertools;
 use nom::branch::alt;
 use nom::character::complete::{char, digit1};
@@ -8,6 +10,8 @@ use nom::combinator::map_res;
 use nom::multi::separated_list0;
 use nom::{sequence::delimited, IResult};
 
+const DAY: u8 = 13;
+
 type Pair = (Packet, Packet);
 use itertools::EitherOrBoth::{Both, Left, Right};
 #[derive(Debug, Clone, PartialEq)]
@@ -35,23 +39,20 @@ fn packet(s: &str) -> IResult<&str, Packet> {
 }
 
 pub fn parse_input(input: &str) -> Result<Vec<Pair>> {
-    input
-        .split("\n\n")
-        .map(|lines| {
-            if let Some((Ok(left), Ok(right))) = lines
-                .lines()
-                .map(|l| match packet(l) {
-                    Ok((_i, j)) => Ok(j),
-                    Err(e) => Err(e),
-                })
-                .collect_tuple()
-            {
-                Ok((left, right))
-            } else {
-                Err(anyhow!("Something wrong happened in parsing {:?}", lines))
-            }
-        })
-        .collect()
+    parse_blocks(DAY, input, |lines| {
+        if let Some((Ok(left), Ok(right))) = lines
+            .lines()
+            .map(|l| match packet(l) {
+                Ok((_i, j)) => Ok(j),
+                Err(e) => Err(e),
+            })
+            .collect_tuple()
+        {
+            Ok((left, right))
+        } else {
+            Err(anyhow!("expected a pair of packets"))
+        }
+    })
 }
 
 fn compare_pair(left: Packet, right: Packet) -> Option<bool> {
@@ -84,8 +85,8 @@ fn compare_pair(left: Packet, right: Packet) -> Option<bool> {
     }
 }
 
-pub fn part1(input: Vec<Pair>) -> usize {
-    input
+pub fn part1(input: Vec<Pair>) -> Result<Answer> {
+    Ok(input
         .into_iter()
         .enumerate()
         .filter_map(|(i, (left, right))| {
@@ -99,22 +100,24 @@ pub fn part1(input: Vec<Pair>) -> usize {
                 None
             }
         })
-        .sum()
+        .sum::<usize>()
+        .into())
 }
 
 pub fn parse_input_p2(input: &str) -> Result<Vec<Packet>> {
     let input_p2 = format!("{input}\n[[2]]\n[[6]]");
     input_p2
         .lines()
-        .filter(|&l| !l.trim().is_empty())
-        .map(|line| match packet(line) {
+        .enumerate()
+        .filter(|&(_, l)| !l.trim().is_empty())
+        .map(|(i, line)| match packet(line) {
             Ok((_i, p)) => Ok(p),
-            Err(_) => Err(anyhow!("Something wrong happened in parsing {:?}", line)),
+            Err(e) => Err(Error::parse(DAY, i + 1, line, e)),
         })
         .collect()
 }
 
-pub fn part2(mut input: Vec<Packet>) -> usize {
+pub fn part2(mut input: Vec<Packet>) -> Result<Answer> {
     input.sort_by(|l, r| match compare_pair(l.clone(), r.clone()) {
         None => Ordering::Equal,
         Some(p) => {
@@ -127,12 +130,13 @@ pub fn part2(mut input: Vec<Packet>) -> usize {
     });
     let two = Packet::Values(vec![Packet::Values(vec![Packet::Num(2)])]);
     let six = Packet::Values(vec![Packet::Values(vec![Packet::Num(6)])]);
-    input
+    Ok(input
         .into_iter()
         .enumerate()
         .filter(|(_, p)| p == &two || p == &six)
         .map(|(i, _)| i + 1)
-        .product()
+        .product::<usize>()
+        .into())
 }
 
 #[cfg(test)]
@@ -208,7 +212,7 @@ mod test {
 [1,[2,[3,[4,[5,6,7]]]],8,9]
 [1,[2,[3,[4,[5,6,0]]]],8,9]";
         if let Ok(i) = parse_input(input) {
-            assert_eq!(part1(i), 13)
+            assert_eq!(part1(i), Ok(13.into()))
         }
     }
 
@@ -238,7 +242,7 @@ mod test {
 [1,[2,[3,[4,[5,6,7]]]],8,9]
 [1,[2,[3,[4,[5,6,0]]]],8,9]";
         if let Ok(i) = parse_input_p2(input) {
-            assert_eq!(part2(i), 140)
+            assert_eq!(part2(i), Ok(140.into()))
         }
     }
 }
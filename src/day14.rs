@@ -1,25 +1,31 @@
 use std::collections::HashSet;
 
+use crate::answer::Answer;
+use crate::error::{parse_lines, Error, Result};
+use anyhow::Context;
 use itertools::Itertools;
 
+const DAY: u8 = 14;
+
 type Coord = (u32, u32);
 
 const START: Coord = (500, 0);
 
-pub fn parse_input(input: &str) -> HashSet<Coord> {
-    input
-        .lines()
-        .flat_map(|line| {
-            line.split(" -> ")
-                .map(|coord| {
-                    if let Some((x, y)) = coord.split_once(',') {
-                        (x.parse().unwrap(), y.parse().unwrap())
-                    } else {
-                        panic!()
-                    }
-                })
+pub fn parse_input(input: &str) -> Result<HashSet<Coord>> {
+    let paths = parse_lines(DAY, input, |line| {
+        line.split(" -> ")
+            .map(|coord| {
+                let (x, y) = coord.split_once(',').context("no comma in coordinates")?;
+                anyhow::Ok((x.parse()?, y.parse()?))
+            })
+            .collect::<anyhow::Result<Vec<Coord>>>()
+    })?;
+    Ok(paths
+        .iter()
+        .flat_map(|path| {
+            path.iter()
                 .tuple_windows()
-                .flat_map(|((x1, y1), (x2, y2))| {
+                .flat_map(|(&(x1, y1), &(x2, y2))| {
                     let mut range = vec![];
                     if x1 == x2 {
                         if y1 < y2 {
@@ -45,9 +51,8 @@ pub fn parse_input(input: &str) -> HashSet<Coord> {
                     }
                     range
                 })
-            // .collect()
         })
-        .collect()
+        .collect())
 }
 
 #[derive(Debug, Clone, Copy)]
@@ -58,28 +63,17 @@ struct Boundaries {
 }
 
 impl Boundaries {
-    fn from(walls: &HashSet<Coord>) -> Boundaries {
-        let x_max = walls
-            .iter()
-            .max_by(|&&(k1, _), &&(k2, _)| k1.cmp(&k2))
-            .unwrap()
-            .0;
-        let y_max = walls
-            .iter()
-            .max_by(|&&(_, k1), &&(_, k2)| k1.cmp(&k2))
-            .unwrap()
-            .1;
-        let x_min = walls
-            .iter()
-            .min_by(|&&(k1, _), &&(k2, _)| k1.cmp(&k2))
-            .unwrap()
-            .0;
+    fn from(walls: &HashSet<Coord>) -> Result<Boundaries> {
+        let no_rocks = || Error::no_solution(DAY, "no rocks");
+        let x_max = walls.iter().map(|&(x, _)| x).max().ok_or_else(no_rocks)?;
+        let y_max = walls.iter().map(|&(_, y)| y).max().ok_or_else(no_rocks)?;
+        let x_min = walls.iter().map(|&(x, _)| x).min().ok_or_else(no_rocks)?;
 
-        Boundaries {
+        Ok(Boundaries {
             x_min,
             x_max,
             y_max,
-        }
+        })
     }
 
     fn is_outside(&self, (x, y): &Coord) -> bool {
@@ -113,8 +107,8 @@ fn pour_sand(walls: &mut HashSet<Coord>, boundaries: &Boundaries) {
     }
 }
 
-pub fn part1(mut input: HashSet<(u32, u32)>) -> usize {
-    let b = Boundaries::from(&input);
+pub fn part1(mut input: HashSet<(u32, u32)>) -> Result<Answer> {
+    let b = Boundaries::from(&input)?;
     let initial_len = input.len();
     let mut len = input.len();
     loop {
@@ -125,20 +119,20 @@ pub fn part1(mut input: HashSet<(u32, u32)>) -> usize {
             break;
         }
     }
-    input.len() - initial_len
+    Ok((input.len() - initial_len).into())
 }
 
 fn add_floor(walls: &mut HashSet<(u32, u32)>, b: &Boundaries) {
     let y_max = b.y_max + 2;
-    for x in (b.x_min - y_max)..=(b.x_max + y_max) {
+    for x in b.x_min.saturating_sub(y_max)..=(b.x_max + y_max) {
         walls.insert((x, y_max));
     }
 }
 
-pub fn part2(mut input: HashSet<(u32, u32)>) -> usize {
-    let mut b = Boundaries::from(&input);
+pub fn part2(mut input: HashSet<(u32, u32)>) -> Result<Answer> {
+    let mut b = Boundaries::from(&input)?;
     add_floor(&mut input, &b);
-    b = Boundaries::from(&input);
+    b = Boundaries::from(&input)?;
     let initial_len = input.len();
     let mut len = input.len();
     loop {
@@ -149,5 +143,5 @@ pub fn part2(mut input: HashSet<(u32, u32)>) -> usize {
             break;
         }
     }
-    input.len() - initial_len
+    Ok((input.len() - initial_len).into())
 }
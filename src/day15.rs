@@ -1,8 +1,11 @@
-use anyhow::{anyhow, Error, Result};
+use crate::answer::Answer;
+use crate::error::{parse_lines, Error, Result};
 use nom::combinator::{map_res, opt, recognize};
 use nom::sequence::{preceded, tuple};
 use nom::{bytes::complete::tag, character::complete::digit1, IResult};
 
+const DAY: u8 = 15;
+
 type Coord = (isize, isize);
 
 fn maybe_neg_num(input: &str) -> IResult<&str, isize> {
@@ -38,7 +41,7 @@ fn parse_line(s: &str) -> IResult<&str, Report> {
                 sensor: (sens_x, sens_y),
                 beacon: (beac_x, beac_y),
             };
-            Ok::<_, Error>(r)
+            Ok::<_, anyhow::Error>(r)
         },
     )(s)
 }
@@ -62,13 +65,9 @@ pub fn parse_input(input: &str) -> Result<Vec<Report>> {
     // Sensor at x=16, y=7: closest beacon is at x=15, y=3
     // Sensor at x=14, y=3: closest beacon is at x=15, y=3
     // Sensor at x=20, y=1: closest beacon is at x=15, y=3";
-    input
-        .lines()
-        .map(|l| match parse_line(l) {
-            Ok((_, p)) => Ok(p),
-            Err(_) => Err(anyhow!("Error in parsing line {}", l)),
-        })
-        .collect()
+    parse_lines(DAY, input, |l| {
+        parse_line(l).map(|(_, p)| p).map_err(|e| e.to_string())
+    })
 }
 
 fn find_blocked_at_line(report: &Report, line: isize) -> Option<(isize, isize)> {
@@ -86,7 +85,10 @@ fn merge_overlapping_intervals(arr: &mut [Coord]) -> Vec<Coord> {
     arr.sort();
 
     let mut result: Vec<Coord> = Vec::new();
-    result.push(arr[0]);
+    match arr.first() {
+        Some(first) => result.push(*first),
+        None => return result,
+    }
 
     for current in arr.iter().skip(1) {
         // let current: Coord = arr[i].clone();
@@ -102,7 +104,7 @@ fn merge_overlapping_intervals(arr: &mut [Coord]) -> Vec<Coord> {
     result
 }
 
-pub fn part1(reports: Vec<Report>) -> isize {
+pub fn part1(reports: Vec<Report>) -> Result<Answer> {
     // const LINE: isize = 10;
     const LINE: isize = 2000000;
 
@@ -111,13 +113,14 @@ pub fn part1(reports: Vec<Report>) -> isize {
         .filter_map(|r| find_blocked_at_line(r, LINE))
         .collect::<Vec<_>>();
 
-    merge_overlapping_intervals(&mut ranges)
+    Ok(merge_overlapping_intervals(&mut ranges)
         .iter()
         .map(|(x, y)| y - x)
-        .sum()
+        .sum::<isize>()
+        .into())
 }
 
-pub fn part2(reports: Vec<Report>) -> isize {
+pub fn part2(reports: Vec<Report>) -> Result<Answer> {
     for y in (0..=4000000).rev() {
         let mut ranges = reports
             .iter()
@@ -126,12 +129,15 @@ pub fn part2(reports: Vec<Report>) -> isize {
         let merged = merge_overlapping_intervals(&mut ranges);
         if merged.len() > 1 {
             if merged[1].0 - merged[0].1 > 1 {
-                return 4000000 * (merged[0].1 + 1) + y;
+                return Ok((4000000 * (merged[0].1 + 1) + y).into());
             }
             println!("{:?}, {:?}", merged, y);
         }
     }
-    0
+    Err(Error::no_solution(
+        DAY,
+        "no spot left for the distress beacon",
+    ))
 }
 
 #[cfg(test)]
@@ -1,3 +1,5 @@
+use crate::answer::Answer;
+use crate::error::{Error, Result};
 use nom::branch::alt;
 use nom::bytes::complete::tag;
 use nom::character::complete::{alpha1, digit1};
@@ -13,7 +15,10 @@ use std::collections::hash_map::Entry::Vacant;
 use std::collections::HashMap;
 use std::sync::Mutex;
 
+const DAY: u8 = 16;
+
 type Tunnels = Graph<String, i32, Undirected>;
+type Distances = HashMap<(NodeIndex, NodeIndex), i32>;
 type CacheP1 = Lazy<Mutex<HashMap<(NodeIndex, Vec<NodeIndex>, i32), i32>>>;
 type CacheP2 = Lazy<Mutex<HashMap<((NodeIndex, NodeIndex), Vec<NodeIndex>, (i32, i32)), i32>>>;
 
@@ -51,53 +56,46 @@ fn parse_line(s: &str) -> IResult<&str, (&str, i32, Vec<&str>)> {
     tuple((valve, flow_rate, tunnels))(s)
 }
 
-pub fn parse_input(input: &str) -> (Vec<Valve>, HashMap<(NodeIndex, NodeIndex), i32>) {
+pub fn parse_input(input: &str) -> Result<(Vec<Valve>, Distances)> {
     let mut valves = Vec::new();
     let mut g: Tunnels = Graph::new_undirected();
     let mut nodes: HashMap<String, NodeIndex> = HashMap::new();
-    for line in input.lines() {
-        if let Ok((_, (valve_name, flow_rate, edges))) = parse_line(line) {
-            let cur;
-            let cur_name = valve_name.to_owned();
-            if let Vacant(e) = nodes.entry(cur_name.clone()) {
-                cur = g.add_node(valve_name.to_string());
-                e.insert(cur);
+    for (i, line) in input.lines().enumerate() {
+        let (_, (valve_name, flow_rate, edges)) =
+            parse_line(line).map_err(|e| Error::parse(DAY, i + 1, line, e))?;
+        let cur;
+        let cur_name = valve_name.to_owned();
+        if let Vacant(e) = nodes.entry(cur_name.clone()) {
+            cur = g.add_node(valve_name.to_string());
+            e.insert(cur);
+        } else {
+            cur = nodes[&cur_name];
+        }
+        let valve = Valve {
+            id: cur,
+            name: valve_name.to_string(),
+            flow_rate,
+        };
+        for edge in edges {
+            let to;
+            let to_name = edge.to_owned();
+            if let Vacant(e) = nodes.entry(to_name.clone()) {
+                to = g.add_node(to_name.to_string());
+                e.insert(to);
             } else {
-                cur = nodes[&cur_name];
-            }
-            let valve = Valve {
-                id: cur,
-                name: valve_name.to_string(),
-                flow_rate,
-            };
-            for edge in edges {
-                let to;
-                let to_name = edge.to_owned();
-                if let Vacant(e) = nodes.entry(to_name.clone()) {
-                    to = g.add_node(to_name.to_string());
-                    e.insert(to);
-                } else {
-                    to = nodes[&to_name];
-                }
-                g.add_edge(cur, to, 1);
+                to = nodes[&to_name];
             }
-            valves.push(valve);
+            g.add_edge(cur, to, 1);
         }
+        valves.push(valve);
     }
 
-    if let Ok(distances) = floyd_warshall(&g, |_| 1) {
-        (valves, distances)
-    } else {
-        panic!()
-    }
+    let distances = floyd_warshall(&g, |_| 1)
+        .map_err(|_| Error::input(DAY, "the tunnels contain a negative cycle"))?;
+    Ok((valves, distances))
 }
 
-fn solve(
-    current: &Valve,
-    valves_to_open: &Vec<Valve>,
-    distances: &HashMap<(NodeIndex, NodeIndex), i32>,
-    time_left: i32,
-) -> i32 {
+fn solve(current: &Valve, valves_to_open: &[Valve], distances: &Distances, time_left: i32) -> i32 {
     if let Some(p) = CACHE_P1.lock().unwrap().get(&(
         current.id,
         valves_to_open.iter().map(|v| v.id).collect(),
@@ -121,7 +119,7 @@ fn solve(
     } else {
         let mut subs = Vec::new();
         for i in 0..valves_to_open.len() {
-            let mut valves_to_open = valves_to_open.clone();
+            let mut valves_to_open = valves_to_open.to_vec();
             let dest = valves_to_open.remove(i);
             // opening takes 1 minute
             let time_left = time_left - 1;
@@ -147,7 +145,9 @@ fn solve(
     }
 }
 
-pub fn part1((valves, distances): (Vec<Valve>, HashMap<(NodeIndex, NodeIndex), i32>)) -> i32 {
+pub fn part1((valves, distances): (Vec<Valve>, Distances)) -> Result<Answer> {
+    // the cache is keyed on node indices, which are only meaningful for one input
+    CACHE_P1.lock().unwrap().clear();
     if let Some(start) = valves.iter().find(|v| v.name == "AA") {
         let time_left = 30;
         let to_open: Vec<Valve> = valves
@@ -156,16 +156,16 @@ pub fn part1((valves, distances): (Vec<Valve>, HashMap<(NodeIndex, NodeIndex), i
             .filter(|v| v.flow_rate > 0)
             .collect();
 
-        solve(start, &to_open, &distances, time_left)
+        Ok(solve(start, &to_open, &distances, time_left).into())
     } else {
-        panic!()
+        Err(Error::input(DAY, "no valve AA"))
     }
 }
 
 fn solve2(
     (current_me, current_elephant): (&Valve, &Valve),
-    valves_to_open: &Vec<Valve>,
-    distances: &HashMap<(NodeIndex, NodeIndex), i32>,
+    valves_to_open: &[Valve],
+    distances: &Distances,
     (time_left_me, time_left_elephant): (i32, i32),
 ) -> i32 {
     if let Some(p) = CACHE_P2.lock().unwrap().get(&(
@@ -265,10 +265,10 @@ fn solve2(
     } else {
         let mut subs = Vec::new();
         for i in 0..valves_to_open.len() {
-            let mut valves_to_open = valves_to_open.clone();
+            let mut valves_to_open = valves_to_open.to_vec();
             let dest_me = valves_to_open.remove(i);
             for j in 0..valves_to_open.len() {
-                let mut valves_to_open = valves_to_open.clone();
+                let mut valves_to_open = valves_to_open.to_vec();
                 let dest_elephant = valves_to_open.remove(j);
                 // opening takes 1 minute
                 let time_left_me = time_left_me - 1;
@@ -343,7 +343,9 @@ fn solve2(
     }
 }
 
-pub fn part2((valves, distances): (Vec<Valve>, HashMap<(NodeIndex, NodeIndex), i32>)) -> i32 {
+pub fn part2((valves, distances): (Vec<Valve>, Distances)) -> Result<Answer> {
+    CACHE_P1.lock().unwrap().clear();
+    CACHE_P2.lock().unwrap().clear();
     if let Some(start) = valves.iter().find(|v| v.name == "AA") {
         let time_left = 26;
         let to_open: Vec<Valve> = valves
@@ -352,9 +354,9 @@ pub fn part2((valves, distances): (Vec<Valve>, HashMap<(NodeIndex, NodeIndex), i
             .filter(|v| v.flow_rate > 0)
             .collect();
 
-        solve2((start, start), &to_open, &distances, (time_left, time_left))
+        Ok(solve2((start, start), &to_open, &distances, (time_left, time_left)).into())
     } else {
-        panic!()
+        Err(Error::input(DAY, "no valve AA"))
     }
 }
 
@@ -403,7 +405,7 @@ mod test {
         Valve BB has flow rate=13; tunnels lead to valves CC, AA
         Valve CC has flow rate=5; tunnels lead to valves AA, BB
         "};
-        let (valves, distances) = parse_input(input);
+        let (valves, distances) = parse_input(input).unwrap();
         assert_eq!(
             valves,
             vec![
@@ -451,9 +453,8 @@ mod test {
                 Valve II has flow rate=0; tunnels lead to valves AA, JJ
                 Valve JJ has flow rate=21; tunnel leads to valve II"
         };
-        let (valves, distances) = parse_input(input);
-        let result = part1((valves, distances));
-        assert_eq!(result, 1651)
+        let result = parse_input(input).and_then(part1);
+        assert_eq!(result, Ok(1651.into()))
     }
 
     #[test]
@@ -470,8 +471,7 @@ mod test {
                 Valve II has flow rate=0; tunnels lead to valves AA, JJ
                 Valve JJ has flow rate=21; tunnel leads to valve II"
         };
-        let (valves, distances) = parse_input(input);
-        let result = part2((valves, distances));
-        assert_eq!(result, 1707)
+        let result = parse_input(input).and_then(part2);
+        assert_eq!(result, Ok(1707.into()))
     }
 }
@@ -1,6 +1,10 @@
-use anyhow::{anyhow, Result};
+use crate::answer::Answer;
+use crate::error::{Error, Result};
+use anyhow::anyhow;
 use std::fmt::Display;
 
+const DAY: u8 = 17;
+
 #[derive(Debug, PartialEq, Clone, Hash, Eq)]
 pub enum Direction {
     Left,
@@ -8,7 +12,7 @@ pub enum Direction {
 }
 
 impl Direction {
-    fn from_char(s: char) -> Result<Self> {
+    fn from_char(s: char) -> anyhow::Result<Self> {
         match s {
             '<' => Ok(Direction::Left),
             '>' => Ok(Direction::Right),
@@ -19,7 +23,14 @@ impl Direction {
 
 pub fn parse_input(input: &str) -> Result<Vec<Direction>> {
     // let input = ">>><<><>><<<>><>>><<<>>><<<><<<>><>><<>>";
-    input.trim().chars().map(Direction::from_char).collect()
+    let line = input.trim();
+    if line.is_empty() {
+        return Err(Error::input(DAY, "no jet pattern"));
+    }
+    line.chars()
+        .map(Direction::from_char)
+        .collect::<anyhow::Result<_>>()
+        .map_err(|e| Error::parse(DAY, 1, line, e))
 }
 
 #[derive(Debug, Clone, Hash, PartialEq, Eq)]
@@ -77,7 +88,7 @@ impl Board {
         if block_len > offset {
             let new_lines = block_len - offset;
             self.lines
-                .append(&mut std::iter::repeat(0).take(new_lines).collect());
+                .append(&mut std::iter::repeat_n(0, new_lines).collect());
             offset += new_lines;
         }
         for i in 0..block_len {
@@ -225,8 +236,11 @@ fn play_part1(directions: Vec<Direction>, iterations: usize) -> usize {
     board.lines.len() - 1
 }
 
-pub fn part1(directions: Vec<Direction>) -> usize {
-    play_part1(directions, 2022)
+pub fn part1(directions: Vec<Direction>) -> Result<Answer> {
+    if directions.is_empty() {
+        return Err(Error::input(DAY, "no jet pattern"));
+    }
+    Ok(play_part1(directions, 2022).into())
 }
 
 fn play_part2(directions: Vec<Direction>, iterations: usize) -> usize {
@@ -278,8 +292,11 @@ fn play_part2(directions: Vec<Direction>, iterations: usize) -> usize {
     unreachable!()
 }
 
-pub fn part2(directions: Vec<Direction>) -> usize {
-    play_part2(directions, 1000000000000)
+pub fn part2(directions: Vec<Direction>) -> Result<Answer> {
+    if directions.is_empty() {
+        return Err(Error::input(DAY, "no jet pattern"));
+    }
+    Ok(play_part2(directions, 1000000000000).into())
 }
 //1524110593571 too low for part 2
 
@@ -296,6 +313,15 @@ mod test {
         }
     }
 
+    #[test]
+    fn test_parse_errors() {
+        assert_eq!(parse_input("\n"), Err(Error::input(DAY, "no jet pattern")));
+        assert_eq!(
+            parse_input("<>x<"),
+            Err(Error::parse(DAY, 1, "<>x<", "unknown direction x"))
+        );
+    }
+
     #[test]
     fn test_shift_single_line() {
         let mut block = Block { shape: vec![30] };
@@ -441,7 +467,7 @@ mod test {
     #[test]
     fn test_part1() {
         if let Ok(directions) = parse_input(">>><<><>><<<>><>>><<<>>><<<><<<>><>><<>>") {
-            assert_eq!(part1(directions), 3068)
+            assert_eq!(part1(directions), Ok(3068.into()))
         } else {
             panic!()
         }
@@ -468,7 +494,7 @@ mod test {
     #[test]
     fn test_part2() {
         if let Ok(directions) = parse_input(">>><<><>><<<>><>>><<<>>><<<><<<>><>><<>>") {
-            assert_eq!(part2(directions), 1514285714288)
+            assert_eq!(part2(directions), Ok(1514285714288usize.into()))
         } else {
             panic!()
         }
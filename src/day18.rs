@@ -1,20 +1,18 @@
-use anyhow::Result;
+use crate::answer::Answer;
+use crate::error::{parse_lines, Result};
+use anyhow::Context;
+use itertools::Itertools;
 use std::collections::HashSet;
 
+const DAY: u8 = 18;
+
 type Point = (i32, i32, i32);
 
 pub fn parse_input(input: &str) -> Result<Vec<Point>> {
-    input
-        .lines()
-        .map(|l| {
-            let mut v = l.split(',');
-            Ok((
-                v.next().unwrap().parse()?,
-                v.next().unwrap().parse()?,
-                v.next().unwrap().parse()?,
-            ))
-        })
-        .collect()
+    parse_lines(DAY, input, |l| {
+        let (x, y, z) = l.split(',').collect_tuple().context("expected x,y,z")?;
+        anyhow::Ok((x.parse()?, y.parse()?, z.parse()?))
+    })
 }
 
 fn neighbors(p: Point) -> HashSet<Point> {
@@ -28,7 +26,7 @@ fn neighbors(p: Point) -> HashSet<Point> {
     ])
 }
 
-pub fn part1(cubes: Vec<Point>) -> usize {
+fn surface(cubes: Vec<Point>) -> usize {
     let set: HashSet<Point> = HashSet::from_iter(cubes.iter().cloned());
     cubes
         .into_iter()
@@ -36,6 +34,10 @@ pub fn part1(cubes: Vec<Point>) -> usize {
         .sum()
 }
 
+pub fn part1(cubes: Vec<Point>) -> Result<Answer> {
+    Ok(surface(cubes).into())
+}
+
 #[allow(clippy::too_many_arguments)]
 fn is_point_inside(
     p: &Point,
@@ -58,13 +60,14 @@ fn is_point_inside(
         && (p.2 + 1..=z_max).any(|z| set.contains(&(p.0, p.1, z)))
 }
 
-pub fn part2(mut cubes: Vec<Point>) -> usize {
-    let x_min = cubes.iter().map(|p| p.0).min().unwrap();
-    let x_max = cubes.iter().map(|p| p.0).max().unwrap();
-    let y_min = cubes.iter().map(|p| p.1).min().unwrap();
-    let y_max = cubes.iter().map(|p| p.1).max().unwrap();
-    let z_min = cubes.iter().map(|p| p.2).min().unwrap();
-    let z_max = cubes.iter().map(|p| p.2).max().unwrap();
+pub fn part2(mut cubes: Vec<Point>) -> Result<Answer> {
+    let bounds = |axis: fn(&Point) -> i32| cubes.iter().map(axis).minmax().into_option();
+    let ((x_min, x_max), (y_min, y_max), (z_min, z_max)) =
+        match (bounds(|p| p.0), bounds(|p| p.1), bounds(|p| p.2)) {
+            (Some(x), Some(y), Some(z)) => (x, y, z),
+            // no cubes, so no surface, as in part 1
+            _ => return Ok(0.into()),
+        };
     let set: HashSet<Point> = HashSet::from_iter(cubes.iter().cloned());
     // we need to discard isolated points, so the function to check if a point is inside
     // works correctly
@@ -91,5 +94,5 @@ pub fn part2(mut cubes: Vec<Point>) -> usize {
             }
         }
     }
-    part1(cubes)
+    Ok(surface(cubes).into())
 }
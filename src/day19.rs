@@ -1,7 +1,9 @@
 use std::collections::HashMap;
 use std::str::FromStr;
 
-use anyhow::{anyhow, Error, Result};
+use crate::answer::Answer;
+use crate::error::{parse_lines, Result};
+use anyhow::anyhow;
 use derivative::Derivative;
 use nom::bytes::complete::tag;
 use nom::character::complete::digit1;
@@ -17,6 +19,8 @@ pub enum Resource {
     Geode,
 }
 
+const DAY: u8 = 19;
+
 type Line = (usize, usize, usize, (usize, usize), (usize, usize));
 type Recipes = HashMap<Resource, HashMap<Resource, usize>>;
 type Robots = HashMap<Resource, usize>;
@@ -52,12 +56,12 @@ pub fn parse_input(input: &str) -> Result<Vec<Blueprint>> {
     // use indoc::indoc;
     // let input = indoc! {"Blueprint 1: Each ore robot costs 4 ore. Each clay robot costs 2 ore. Each obsidian robot costs 3 ore and 14 clay. Each geode robot costs 2 ore and 7 obsidian.
     // Blueprint 2: Each ore robot costs 2 ore. Each clay robot costs 3 ore. Each obsidian robot costs 3 ore and 8 clay. Each geode robot costs 3 ore and 12 obsidian."};
-    input.lines().map(|l| l.parse()).collect()
+    parse_lines(DAY, input, |l| l.parse())
 }
 
 impl FromStr for Blueprint {
-    type Err = Error;
-    fn from_str(s: &str) -> Result<Self> {
+    type Err = anyhow::Error;
+    fn from_str(s: &str) -> anyhow::Result<Self> {
         if let Ok((
             _,
             (
@@ -345,17 +349,17 @@ impl State {
     }
 }
 
-pub fn part1(blueprints: Vec<Blueprint>) -> usize {
+pub fn part1(blueprints: Vec<Blueprint>) -> Result<Answer> {
     let mut final_state = vec![];
     for blueprint in blueprints {
         let id = blueprint.id;
         let state = State::from_blueprint(blueprint);
         final_state.push(id * state.explore());
     }
-    final_state.iter().sum()
+    Ok(final_state.iter().sum::<usize>().into())
 }
 
-pub fn part2(mut blueprints: Vec<Blueprint>) -> usize {
+pub fn part2(mut blueprints: Vec<Blueprint>) -> Result<Answer> {
     let mut final_state = vec![];
     blueprints.truncate(3);
     for blueprint in blueprints {
@@ -364,7 +368,7 @@ pub fn part2(mut blueprints: Vec<Blueprint>) -> usize {
 
         final_state.push(state.explore());
     }
-    final_state.iter().product()
+    Ok(final_state.iter().product::<usize>().into())
 }
 
 #[cfg(test)]
@@ -376,7 +380,7 @@ pub mod test {
 
     #[test]
     // #[ignore]
-    fn test_explore() -> Result<()> {
+    fn test_explore() -> anyhow::Result<()> {
         let b: Blueprint = "Blueprint 1: Each ore robot costs 4 ore. Each clay robot costs 2 ore. Each obsidian robot costs 3 ore and 14 clay. Each geode robot costs 2 ore and 7 obsidian.".parse()?;
         let state = State::from_blueprint(b);
         let result = state.explore();
@@ -385,7 +389,7 @@ pub mod test {
     }
 
     #[test]
-    fn test_max_needed_robot() -> Result<()> {
+    fn test_max_needed_robot() -> anyhow::Result<()> {
         let b: Blueprint = "Blueprint 1: Each ore robot costs 4 ore. Each clay robot costs 2 ore. Each obsidian robot costs 3 ore and 14 clay. Each geode robot costs 2 ore and 7 obsidian.".parse()?;
         let mut expected = HashMap::new();
         expected.insert(Resource::Ore, 4);
@@ -396,7 +400,7 @@ pub mod test {
     }
 
     #[test]
-    fn test_next_states_basic() -> Result<()> {
+    fn test_next_states_basic() -> anyhow::Result<()> {
         let b: Blueprint = "Blueprint 1: Each ore robot costs 4 ore. Each clay robot costs 2 ore. Each obsidian robot costs 3 ore and 14 clay. Each geode robot costs 2 ore and 7 obsidian.".parse()?;
         let state = State::from_blueprint(b.clone());
         let start_time = state.time_left;
@@ -428,7 +432,7 @@ pub mod test {
 
         let mut result = state.next_states();
         // for consistent ordering when comparing
-        result.sort_by(|(a, _), (b, _)| a.cmp(b));
+        result.sort_by_key(|(a, _)| *a);
 
         // ore robot built
         assert_eq!(result[0].0, Resource::Ore);
@@ -441,7 +445,7 @@ pub mod test {
     }
 
     #[test]
-    fn test_next_states_basic_step_by_step() -> Result<()> {
+    fn test_next_states_basic_step_by_step() -> anyhow::Result<()> {
         let b: Blueprint = "Blueprint 1: Each ore robot costs 4 ore. Each clay robot costs 2 ore. Each obsidian robot costs 3 ore and 14 clay. Each geode robot costs 2 ore and 7 obsidian.".parse()?;
         let state = State::from_blueprint(b);
         let mut total_geodes = 0;
@@ -450,7 +454,7 @@ pub mod test {
         println!("initial state {state:?}\n");
         // build clay robot
         let mut result = state.next_states();
-        result.sort_by(|(a, _), (b, _)| a.cmp(b));
+        result.sort_by_key(|(a, _)| *a);
         assert_eq!(result[1].0, Resource::Clay);
         let state = &result[1].1;
         println!("== Minute {:2} ==", 24 - state.time_left);
@@ -458,7 +462,7 @@ pub mod test {
 
         // build clay robot
         let mut result = state.next_states();
-        result.sort_by(|(a, _), (b, _)| a.cmp(b));
+        result.sort_by_key(|(a, _)| *a);
         assert_eq!(result[1].0, Resource::Clay);
         let state = &result[1].1;
         println!("== Minute {:2} ==", 24 - state.time_left);
@@ -466,7 +470,7 @@ pub mod test {
 
         // build clay robot
         let mut result = state.next_states();
-        result.sort_by(|(a, _), (b, _)| a.cmp(b));
+        result.sort_by_key(|(a, _)| *a);
         assert_eq!(result[1].0, Resource::Clay);
         let state = &result[1].1;
         println!("== Minute {:2} ==", 24 - state.time_left);
@@ -474,7 +478,7 @@ pub mod test {
 
         // build obsidian robot
         let mut result = state.next_states();
-        result.sort_by(|(a, _), (b, _)| a.cmp(b));
+        result.sort_by_key(|(a, _)| *a);
         assert_eq!(result[2].0, Resource::Obsidian);
         let state = &result[2].1;
         println!("== Minute {:2} ==", 24 - state.time_left);
@@ -482,7 +486,7 @@ pub mod test {
 
         // build clay robot
         let mut result = state.next_states();
-        result.sort_by(|(a, _), (b, _)| a.cmp(b));
+        result.sort_by_key(|(a, _)| *a);
         assert_eq!(result[1].0, Resource::Clay);
         let state = &result[1].1;
         println!("== Minute {:2} ==", 24 - state.time_left);
@@ -490,7 +494,7 @@ pub mod test {
 
         // build obsidian robot
         let mut result = state.next_states();
-        result.sort_by(|(a, _), (b, _)| a.cmp(b));
+        result.sort_by_key(|(a, _)| *a);
         assert_eq!(result[2].0, Resource::Obsidian);
         let state = &result[2].1;
         println!("== Minute {:2} ==", 24 - state.time_left);
@@ -498,7 +502,7 @@ pub mod test {
 
         // build geode robot
         let mut result = state.next_states();
-        result.sort_by(|(a, _), (b, _)| a.cmp(b));
+        result.sort_by_key(|(a, _)| *a);
         assert_eq!(result[3].0, Resource::Geode);
         let state = &result[3].1;
 
@@ -509,7 +513,7 @@ pub mod test {
 
         // build geode robot
         let mut result = state.next_states();
-        result.sort_by(|(a, _), (b, _)| a.cmp(b));
+        result.sort_by_key(|(a, _)| *a);
         println!("\n{result:?}");
         assert_eq!(result[3].0, Resource::Geode);
         let state = &result[3].1;
@@ -527,7 +531,7 @@ pub mod test {
     }
 
     #[test]
-    fn test_part2_step_by_step() -> Result<()> {
+    fn test_part2_step_by_step() -> anyhow::Result<()> {
         let b: Blueprint = "Blueprint 1: Each ore robot costs 4 ore. Each clay robot costs 2 ore. Each obsidian robot costs 3 ore and 14 clay. Each geode robot costs 2 ore and 7 obsidian.".parse()?;
         let mut state = State::from_blueprint(b);
         state.time_left = 32;
@@ -538,7 +542,7 @@ pub mod test {
 
         // build ore robot
         let mut result = state.next_states();
-        result.sort_by(|(a, _), (b, _)| a.cmp(b));
+        result.sort_by_key(|(a, _)| *a);
         assert_eq!(result[0].0, Resource::Ore);
         let state = &result[0].1;
         println!("== Minute {:2} ==", 32 - state.time_left);
@@ -546,7 +550,7 @@ pub mod test {
 
         // build clay robot
         let mut result = state.next_states();
-        result.sort_by(|(a, _), (b, _)| a.cmp(b));
+        result.sort_by_key(|(a, _)| *a);
         assert_eq!(result[1].0, Resource::Clay);
         let state = &result[1].1;
         println!("== Minute {:2} ==", 32 - state.time_left);
@@ -554,7 +558,7 @@ pub mod test {
 
         // build clay robot
         let mut result = state.next_states();
-        result.sort_by(|(a, _), (b, _)| a.cmp(b));
+        result.sort_by_key(|(a, _)| *a);
         assert_eq!(result[1].0, Resource::Clay);
         let state = &result[1].1;
         println!("== Minute {:2} ==", 32 - state.time_left);
@@ -562,7 +566,7 @@ pub mod test {
 
         // build clay robot
         let mut result = state.next_states();
-        result.sort_by(|(a, _), (b, _)| a.cmp(b));
+        result.sort_by_key(|(a, _)| *a);
         assert_eq!(result[1].0, Resource::Clay);
         let state = &result[1].1;
         println!("== Minute {:2} ==", 32 - state.time_left);
@@ -570,7 +574,7 @@ pub mod test {
 
         // build clay robot
         let mut result = state.next_states();
-        result.sort_by(|(a, _), (b, _)| a.cmp(b));
+        result.sort_by_key(|(a, _)| *a);
         assert_eq!(result[1].0, Resource::Clay);
         let state = &result[1].1;
         println!("== Minute {:2} ==", 32 - state.time_left);
@@ -578,7 +582,7 @@ pub mod test {
 
         // build clay robot
         let mut result = state.next_states();
-        result.sort_by(|(a, _), (b, _)| a.cmp(b));
+        result.sort_by_key(|(a, _)| *a);
         assert_eq!(result[1].0, Resource::Clay);
         let state = &result[1].1;
         println!("== Minute {:2} ==", 32 - state.time_left);
@@ -586,7 +590,7 @@ pub mod test {
 
         // build clay robot
         let mut result = state.next_states();
-        result.sort_by(|(a, _), (b, _)| a.cmp(b));
+        result.sort_by_key(|(a, _)| *a);
         assert_eq!(result[1].0, Resource::Clay);
         let state = &result[1].1;
         println!("== Minute {:2} ==", 32 - state.time_left);
@@ -594,7 +598,7 @@ pub mod test {
 
         // build clay robot
         let mut result = state.next_states();
-        result.sort_by(|(a, _), (b, _)| a.cmp(b));
+        result.sort_by_key(|(a, _)| *a);
         assert_eq!(result[1].0, Resource::Clay);
         let state = &result[1].1;
         println!("== Minute {:2} ==", 32 - state.time_left);
@@ -602,7 +606,7 @@ pub mod test {
 
         // build obsidian robot
         let mut result = state.next_states();
-        result.sort_by(|(a, _), (b, _)| a.cmp(b));
+        result.sort_by_key(|(a, _)| *a);
         assert_eq!(result[2].0, Resource::Obsidian);
         let state = &result[2].1;
         println!("== Minute {:2} ==", 32 - state.time_left);
@@ -610,7 +614,7 @@ pub mod test {
 
         // build obsidian robot
         let mut result = state.next_states();
-        result.sort_by(|(a, _), (b, _)| a.cmp(b));
+        result.sort_by_key(|(a, _)| *a);
         assert_eq!(result[2].0, Resource::Obsidian);
         let state = &result[2].1;
         println!("== Minute {:2} ==", 32 - state.time_left);
@@ -618,7 +622,7 @@ pub mod test {
 
         // build obsidian robot
         let mut result = state.next_states();
-        result.sort_by(|(a, _), (b, _)| a.cmp(b));
+        result.sort_by_key(|(a, _)| *a);
         assert_eq!(result[2].0, Resource::Obsidian);
         let state = &result[2].1;
         println!("== Minute {:2} ==", 32 - state.time_left);
@@ -626,7 +630,7 @@ pub mod test {
 
         // build obsidian robot
         let mut result = state.next_states();
-        result.sort_by(|(a, _), (b, _)| a.cmp(b));
+        result.sort_by_key(|(a, _)| *a);
         assert_eq!(result[2].0, Resource::Obsidian);
         let state = &result[2].1;
         println!("== Minute {:2} ==", 32 - state.time_left);
@@ -634,7 +638,7 @@ pub mod test {
 
         // build geode robot
         let mut result = state.next_states();
-        result.sort_by(|(a, _), (b, _)| a.cmp(b));
+        result.sort_by_key(|(a, _)| *a);
         assert_eq!(result[3].0, Resource::Geode);
         let state = &result[3].1;
         num_geodes += state.time_left;
@@ -644,7 +648,7 @@ pub mod test {
 
         // build obsidian robot
         let mut result = state.next_states();
-        result.sort_by(|(a, _), (b, _)| a.cmp(b));
+        result.sort_by_key(|(a, _)| *a);
         assert_eq!(result[2].0, Resource::Obsidian);
         let state = &result[2].1;
         println!("== Minute {:2} ==", 32 - state.time_left);
@@ -652,7 +656,7 @@ pub mod test {
 
         // build geode robot
         let mut result = state.next_states();
-        result.sort_by(|(a, _), (b, _)| a.cmp(b));
+        result.sort_by_key(|(a, _)| *a);
         assert_eq!(result[3].0, Resource::Geode);
         let state = &result[3].1;
         num_geodes += state.time_left;
@@ -662,7 +666,7 @@ pub mod test {
 
         // build geode robot
         let mut result = state.next_states();
-        result.sort_by(|(a, _), (b, _)| a.cmp(b));
+        result.sort_by_key(|(a, _)| *a);
         assert_eq!(result[3].0, Resource::Geode);
         let state = &result[3].1;
         num_geodes += state.time_left;
@@ -672,7 +676,7 @@ pub mod test {
 
         // build geode robot
         let mut result = state.next_states();
-        result.sort_by(|(a, _), (b, _)| a.cmp(b));
+        result.sort_by_key(|(a, _)| *a);
         assert_eq!(result[3].0, Resource::Geode);
         let state = &result[3].1;
         num_geodes += state.time_left;
@@ -682,7 +686,7 @@ pub mod test {
 
         // build geode robot
         let mut result = state.next_states();
-        result.sort_by(|(a, _), (b, _)| a.cmp(b));
+        result.sort_by_key(|(a, _)| *a);
         assert_eq!(result[3].0, Resource::Geode);
         let state = &result[3].1;
         num_geodes += state.time_left;
@@ -692,7 +696,7 @@ pub mod test {
 
         // build geode robot
         let mut result = state.next_states();
-        result.sort_by(|(a, _), (b, _)| a.cmp(b));
+        result.sort_by_key(|(a, _)| *a);
         assert_eq!(result[2].0, Resource::Geode);
         let state = &result[2].1;
         num_geodes += state.time_left;
@@ -702,7 +706,7 @@ pub mod test {
 
         // build geode robot
         let mut result = state.next_states();
-        result.sort_by(|(a, _), (b, _)| a.cmp(b));
+        result.sort_by_key(|(a, _)| *a);
         assert_eq!(result[2].0, Resource::Geode);
         let state = &result[2].1;
         num_geodes += state.time_left;
@@ -712,7 +716,7 @@ pub mod test {
 
         // build geode robot
         let mut result = state.next_states();
-        result.sort_by(|(a, _), (b, _)| a.cmp(b));
+        result.sort_by_key(|(a, _)| *a);
         assert_eq!(result[2].0, Resource::Geode);
         let state = &result[2].1;
         num_geodes += state.time_left;
@@ -722,7 +726,7 @@ pub mod test {
 
         // build geode robot
         let mut result = state.next_states();
-        result.sort_by(|(a, _), (b, _)| a.cmp(b));
+        result.sort_by_key(|(a, _)| *a);
         assert_eq!(result[0].0, Resource::Geode);
         let state = &result[0].1;
         num_geodes += state.time_left;
@@ -740,7 +744,7 @@ pub mod test {
     }
 
     #[test]
-    fn test_compute_resources() -> Result<()> {
+    fn test_compute_resources() -> anyhow::Result<()> {
         let b: Blueprint = "Blueprint 1: Each ore robot costs 4 ore. Each clay robot costs 2 ore. Each obsidian robot costs 3 ore and 14 clay. Each geode robot costs 2 ore and 7 obsidian.".parse()?;
         let mut state = State::from_blueprint(b);
 
@@ -758,7 +762,7 @@ pub mod test {
     }
 
     #[test]
-    fn test_next_robots_to_consider() -> Result<()> {
+    fn test_next_robots_to_consider() -> anyhow::Result<()> {
         let b: Blueprint = "Blueprint 1: Each ore robot costs 4 ore. Each clay robot costs 2 ore. Each obsidian robot costs 3 ore and 14 clay. Each geode robot costs 2 ore and 7 obsidian.".parse()?;
         let state = State::from_blueprint(b);
         let robots: HashSet<Resource> = HashSet::from_iter(state.next_robots_to_consider());
@@ -773,7 +777,7 @@ pub mod test {
     }
 
     #[test]
-    fn test_minutes_until_robot_ready() -> Result<()> {
+    fn test_minutes_until_robot_ready() -> anyhow::Result<()> {
         let b: Blueprint = "Blueprint 1: Each ore robot costs 4 ore. Each clay robot costs 2 ore. Each obsidian robot costs 3 ore and 14 clay. Each geode robot costs 2 ore and 7 obsidian.".parse()?;
         let mut s = State::from_blueprint(b);
         s.resources.insert(Resource::Ore, 2);
@@ -792,7 +796,7 @@ pub mod test {
     }
 
     #[test]
-    fn test_minutes_until_robot_ready_2() -> Result<()> {
+    fn test_minutes_until_robot_ready_2() -> anyhow::Result<()> {
         let b: Blueprint = "Blueprint 1: Each ore robot costs 4 ore. Each clay robot costs 2 ore. Each obsidian robot costs 3 ore and 14 clay. Each geode robot costs 2 ore and 7 obsidian.".parse()?;
         let mut s = State::from_blueprint(b);
         s.resources.insert(Resource::Ore, 3);
@@ -813,7 +817,7 @@ pub mod test {
     }
 
     #[test]
-    fn test_next_states_more_complex() -> Result<()> {
+    fn test_next_states_more_complex() -> anyhow::Result<()> {
         let b: Blueprint = "Blueprint 1: Each ore robot costs 4 ore. Each clay robot costs 2 ore. Each obsidian robot costs 3 ore and 14 clay. Each geode robot costs 2 ore and 7 obsidian.".parse()?;
         let mut state = State::from_blueprint(b.clone());
         let start_time = 24;
@@ -831,7 +835,7 @@ pub mod test {
 
         let mut result = state.next_states();
         // for consistent ordering when comparing
-        result.sort_by(|(a, _), (b, _)| a.cmp(b));
+        result.sort_by_key(|(a, _)| *a);
 
         // We can :
         // - produce an Ore robot in 2 minutes. So next time we are ready is 3 minutes
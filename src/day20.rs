@@ -1,19 +1,28 @@
 use std::collections::VecDeque;
 
-use anyhow::Result;
+use crate::answer::Answer;
+use crate::error::{parse_lines, Error, Result};
+
+const DAY: u8 = 20;
 
 pub fn parse_input(input: &str) -> Result<Vec<isize>> {
-    input.lines().map(|l| Ok(l.parse()?)).collect()
+    parse_lines(DAY, input, |l| l.parse())
 }
 
-fn score(r: &VecDeque<(usize, isize)>) -> isize {
-    let zero = r.iter().position(|&(_, v)| v == 0).unwrap();
+fn score(r: &VecDeque<(usize, isize)>) -> Result<isize> {
+    let zero = r
+        .iter()
+        .position(|&(_, v)| v == 0)
+        .ok_or_else(|| Error::input(DAY, "no 0 in the file"))?;
     let len = r.len();
-    r[(zero + 1000) % len].1 + r[(zero + 2000) % len].1 + r[(zero + 3000) % len].1
+    Ok(r[(zero + 1000) % len].1 + r[(zero + 2000) % len].1 + r[(zero + 3000) % len].1)
 }
 
 fn rotate<T>(d: &mut VecDeque<T>, val: isize) {
     let len = d.len();
+    if len == 0 {
+        return;
+    }
     if val >= 0 {
         d.rotate_left(val as usize % len)
     } else {
@@ -30,16 +39,16 @@ fn mix(d: &mut VecDeque<(usize, isize)>, val: (usize, isize)) {
     d.push_back(val);
 }
 
-pub fn part1(input: Vec<isize>) -> isize {
+pub fn part1(input: Vec<isize>) -> Result<Answer> {
     let mut queue = VecDeque::from_iter(input.into_iter().enumerate());
     let mut list = queue.clone();
     while let Some(val) = queue.pop_front() {
         mix(&mut list, val);
     }
-    score(&list)
+    score(&list).map(Answer::from)
 }
 
-pub fn part2(input: Vec<isize>) -> isize {
+pub fn part2(input: Vec<isize>) -> Result<Answer> {
     let decryption_key = 811589153;
     let input = input.into_iter().map(|i| i * decryption_key).enumerate();
     let initial_queue = VecDeque::from_iter(input);
@@ -50,5 +59,5 @@ pub fn part2(input: Vec<isize>) -> isize {
             mix(&mut list, val);
         }
     }
-    score(&list)
+    score(&list).map(Answer::from)
 }
@@ -1,13 +1,15 @@
 use std::collections::HashMap;
 
-use anyhow::{anyhow, Result};
+use crate::answer::Answer;
+use crate::error::{parse_lines, Error, Result};
 use nom::branch::alt;
 use nom::bytes::complete::tag;
 use nom::character::complete::{alpha1, digit1};
-use nom::error::Error;
 use nom::sequence::{separated_pair, terminated};
 use nom::{Err, IResult};
 
+const DAY: u8 = 21;
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum MonkeyVal {
     Val(isize),
@@ -34,7 +36,7 @@ fn number(s: &str) -> IResult<&str, MonkeyVal> {
     match digit1(s) {
         Ok((i, r)) => match r.parse() {
             Ok(n) => Ok((i, MonkeyVal::Val(n))),
-            Err(_) => Err(Err::Error(Error::new(
+            Err(_) => Err(Err::Error(nom::error::Error::new(
                 "error parsing",
                 nom::error::ErrorKind::Digit,
             ))),
@@ -82,38 +84,39 @@ fn line(s: &str) -> IResult<&str, (String, MonkeyVal)> {
     }
 }
 pub fn parse_input(input: &str) -> Result<HashMap<String, MonkeyVal>> {
-    input
-        .lines()
-        .map(|l| match line(l) {
-            Ok((_, m)) => Ok(m),
-            Err(e) => {
-                println!("{:?}", e);
-                Err(anyhow!("parsing error"))
-            }
-        })
-        .collect()
+    let monkeys = parse_lines(DAY, input, |l| match line(l) {
+        Ok(("", m)) => Ok(m),
+        Ok((rest, _)) => Err(format!("unexpected '{}'", rest)),
+        Err(e) => Err(e.to_string()),
+    })?;
+    Ok(monkeys.into_iter().collect())
+}
+
+fn get<'a, T>(name: &str, d: &'a HashMap<String, T>) -> Result<&'a T> {
+    d.get(name)
+        .ok_or_else(|| Error::input(DAY, format!("no monkey named {}", name)))
 }
 
-fn find_monkey_value(name: &String, d: &HashMap<String, MonkeyVal>) -> isize {
-    match &d[name] {
+fn find_monkey_value(name: &str, d: &HashMap<String, MonkeyVal>) -> Result<isize> {
+    Ok(match get(name, d)? {
         MonkeyVal::Val(n) => *n,
         MonkeyVal::Op(Operation::Add(n1, n2)) => {
-            find_monkey_value(n1, d) + find_monkey_value(n2, d)
+            find_monkey_value(n1, d)? + find_monkey_value(n2, d)?
         }
         MonkeyVal::Op(Operation::Sub(n1, n2)) => {
-            find_monkey_value(n1, d) - find_monkey_value(n2, d)
+            find_monkey_value(n1, d)? - find_monkey_value(n2, d)?
         }
         MonkeyVal::Op(Operation::Mul(n1, n2)) => {
-            find_monkey_value(n1, d) * find_monkey_value(n2, d)
-        }
-        MonkeyVal::Op(Operation::Div(n1, n2)) => {
-            find_monkey_value(n1, d) / find_monkey_value(n2, d)
+            find_monkey_value(n1, d)? * find_monkey_value(n2, d)?
         }
-    }
+        MonkeyVal::Op(Operation::Div(n1, n2)) => find_monkey_value(n1, d)?
+            .checked_div(find_monkey_value(n2, d)?)
+            .ok_or_else(|| Error::no_solution(DAY, format!("{} divides by 0", name)))?,
+    })
 }
 
-pub fn part1(input: HashMap<String, MonkeyVal>) -> isize {
-    find_monkey_value(&"root".into(), &input)
+pub fn part1(input: HashMap<String, MonkeyVal>) -> Result<Answer> {
+    find_monkey_value("root", &input).map(Answer::from)
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -129,8 +132,8 @@ struct Eval {
     multiplier: f64,
 }
 
-fn eval(name: &String, d: &HashMap<String, MonkeyValP2>) -> Eval {
-    match &d[name] {
+fn eval(name: &str, d: &HashMap<String, MonkeyValP2>) -> Result<Eval> {
+    Ok(match get(name, d)? {
         MonkeyValP2::Unknown => Eval {
             constant: 0.,
             multiplier: 1.,
@@ -144,11 +147,11 @@ fn eval(name: &String, d: &HashMap<String, MonkeyValP2>) -> Eval {
                 let Eval {
                     constant: left_const,
                     multiplier: left_mult,
-                } = eval(n1, d);
+                } = eval(n1, d)?;
                 let Eval {
                     constant: right_const,
                     multiplier: right_mult,
-                } = eval(n2, d);
+                } = eval(n2, d)?;
                 Eval {
                     constant: left_const + right_const,
                     multiplier: left_mult + right_mult,
@@ -158,11 +161,11 @@ fn eval(name: &String, d: &HashMap<String, MonkeyValP2>) -> Eval {
                 let Eval {
                     constant: left_const,
                     multiplier: left_mult,
-                } = eval(n1, d);
+                } = eval(n1, d)?;
                 let Eval {
                     constant: right_const,
                     multiplier: right_mult,
-                } = eval(n2, d);
+                } = eval(n2, d)?;
                 Eval {
                     constant: left_const - right_const,
                     multiplier: left_mult - right_mult,
@@ -172,11 +175,11 @@ fn eval(name: &String, d: &HashMap<String, MonkeyValP2>) -> Eval {
                 let Eval {
                     constant: left_const,
                     multiplier: left_mult,
-                } = eval(n1, d);
+                } = eval(n1, d)?;
                 let Eval {
                     constant: right_const,
                     multiplier: right_mult,
-                } = eval(n2, d);
+                } = eval(n2, d)?;
                 if left_mult == 0. {
                     Eval {
                         constant: left_const * right_const,
@@ -188,24 +191,25 @@ fn eval(name: &String, d: &HashMap<String, MonkeyValP2>) -> Eval {
                         multiplier: left_mult * right_const,
                     }
                 } else {
-                    println!("eval left  {left_mult} * x +  {left_const}");
-                    println!("eval right {right_mult} * x + {right_const}");
-                    panic!("multiplying by x");
+                    return Err(Error::no_solution(
+                        DAY,
+                        format!("{} multiplies humn by itself", name),
+                    ));
                 }
             }
             Operation::Div(n1, n2) => {
                 let Eval {
                     constant: left_const,
                     multiplier: left_mult,
-                } = eval(n1, d);
+                } = eval(n1, d)?;
                 let Eval {
                     constant: right_const,
                     multiplier: right_mult,
-                } = eval(n2, d);
+                } = eval(n2, d)?;
                 if right_mult != 0. {
-                    panic!("dividing by x");
+                    return Err(Error::no_solution(DAY, format!("{} divides by humn", name)));
                 } else if right_const == 0. {
-                    panic!("division by 0");
+                    return Err(Error::no_solution(DAY, format!("{} divides by 0", name)));
                 } else {
                     Eval {
                         constant: left_const / right_const,
@@ -214,10 +218,10 @@ fn eval(name: &String, d: &HashMap<String, MonkeyValP2>) -> Eval {
                 }
             }
         },
-    }
+    })
 }
 
-pub fn part2(input: HashMap<String, MonkeyVal>) -> isize {
+pub fn part2(input: HashMap<String, MonkeyVal>) -> Result<Answer> {
     let d: HashMap<String, MonkeyValP2> = input
         .into_iter()
         .map(|(k, v)| {
@@ -243,24 +247,26 @@ pub fn part2(input: HashMap<String, MonkeyVal>) -> isize {
             constant: right_const,
             multiplier: right_mult,
         },
-    ) = match &d["root"] {
-        MonkeyValP2::Unknown => unreachable!(),
-        MonkeyValP2::Val(_) => unreachable!(),
+    ) = match get("root", &d)? {
+        MonkeyValP2::Unknown | MonkeyValP2::Val(_) => {
+            return Err(Error::input(DAY, "root does not compare two monkeys"))
+        }
         MonkeyValP2::Op(op) => match op {
-            Operation::Add(l, r) => (eval(l, &d), eval(r, &d)),
-            Operation::Sub(l, r) => (eval(l, &d), eval(r, &d)),
-            Operation::Mul(l, r) => (eval(l, &d), eval(r, &d)),
-            Operation::Div(l, r) => (eval(l, &d), eval(r, &d)),
+            Operation::Add(l, r)
+            | Operation::Sub(l, r)
+            | Operation::Mul(l, r)
+            | Operation::Div(l, r) => (eval(l, &d)?, eval(r, &d)?),
         },
     };
-
-    ((right_const - left_const) / (left_mult - right_mult)) as isize
+    if left_mult == right_mult {
+        return Err(Error::no_solution(DAY, "root does not depend on humn"));
+    }
+    Ok((((right_const - left_const) / (left_mult - right_mult)) as isize).into())
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use anyhow::Result;
     use indoc::indoc;
 
     #[test]
@@ -341,9 +347,19 @@ mod tests {
             drzm: hmdt - zczc
             hmdt: 32"};
         let d = parse_input(input)?;
-        assert_eq!(part1(d), 152);
+        assert_eq!(part1(d), Ok(152.into()));
+        Ok(())
+    }
+
+    #[test]
+    fn test_errors() -> Result<()> {
+        let d = parse_input("root: aaaa / bbbb\naaaa: 4\nbbbb: 0")?;
+        assert_eq!(part1(d), Err(Error::no_solution(DAY, "root divides by 0")));
+        let d = parse_input("root: aaaa + bbbb\naaaa: 4")?;
+        assert_eq!(part1(d), Err(Error::input(DAY, "no monkey named bbbb")));
         Ok(())
     }
+
     #[test]
     fn test_part2() -> Result<()> {
         let input = indoc! {
@@ -363,7 +379,7 @@ mod tests {
             drzm: hmdt - zczc
             hmdt: 32"};
         let d = parse_input(input)?;
-        assert_eq!(part2(d), 301);
+        assert_eq!(part2(d), Ok(301.into()));
         Ok(())
     }
 }
@@ -1,6 +1,7 @@
 use std::collections::HashMap;
 
-use anyhow::{Error, Result};
+use crate::answer::Answer;
+use crate::error::{Error, Result};
 use nom::branch::alt;
 use nom::character::complete::{char, digit1};
 use nom::combinator::map_res;
@@ -10,6 +11,8 @@ use nom::sequence::tuple;
 use nom::IResult;
 use num::Complex;
 
+const DAY: u8 = 22;
+
 type Map = HashMap<Complex<isize>, bool>;
 type Path = Vec<(isize, Option<Complex<isize>>)>;
 
@@ -18,8 +21,8 @@ fn _draw_board(map: &Map, position: &Complex<isize>, direction: &Complex<isize>)
 
     let dur = time::Duration::from_millis(100);
     // print!("\x1B[2J\x1B[1;1H");
-    let max_x = map.iter().map(|(p, _)| p.re).max().unwrap();
-    let max_y = map.iter().map(|(p, _)| p.im).max().unwrap();
+    let max_x = map.keys().map(|p| p.re).max().unwrap();
+    let max_y = map.keys().map(|p| p.im).max().unwrap();
     for y in 0..=max_y {
         for x in 0..=max_x {
             let c = Complex::new(x, y);
@@ -64,8 +67,8 @@ fn parse_map(map: &str) -> Map {
 
 fn direction(s: &str) -> IResult<&str, Complex<isize>> {
     alt((
-        map_res(char('L'), |_| Ok::<_, Error>(Complex::new(0, -1))),
-        map_res(char('R'), |_| Ok::<_, Error>(Complex::new(0, 1))),
+        map_res(char('L'), |_| Ok::<_, anyhow::Error>(Complex::new(0, -1))),
+        map_res(char('R'), |_| Ok::<_, anyhow::Error>(Complex::new(0, 1))),
     ))(s)
 }
 
@@ -94,13 +97,28 @@ pub fn parse_input(input: &str) -> Result<(Map, Path)> {
     //         ......#.
 
     // 10R5L5R10L4R5L5"};
-    if let Some((map, instr)) = input.split_once("\n\n") {
-        let map = parse_map(map);
-        if let Ok((_, instr)) = parse_instr(instr) {
-            return Ok((map, instr));
-        }
+    let (map, instr) = input
+        .split_once("\n\n")
+        .ok_or_else(|| Error::input(DAY, "no empty line between the map and the path"))?;
+    let line = map.lines().count() + 2;
+    let path = instr.trim_end();
+    match parse_instr(path) {
+        Ok(("", instr)) => Ok((parse_map(map), instr)),
+        Ok((rest, _)) => Err(Error::parse(
+            DAY,
+            line,
+            path,
+            format!("unexpected '{}'", rest),
+        )),
+        Err(e) => Err(Error::parse(DAY, line, path, e)),
     }
-    unreachable!()
+}
+
+fn start(map: &Map) -> Result<isize> {
+    map.iter()
+        .filter_map(|(Complex { re: i, im: j }, wall)| (j == &0 && !wall).then_some(*i))
+        .min()
+        .ok_or_else(|| Error::input(DAY, "no open tile on the first row"))
 }
 
 fn score(position: &Complex<isize>, direction: &Complex<isize>) -> isize {
@@ -114,12 +132,8 @@ fn score(position: &Complex<isize>, direction: &Complex<isize>) -> isize {
     1000 * (position.im + 1) + 4 * (position.re + 1) + facing
 }
 
-pub fn part1((map, instr): (Map, Path)) -> isize {
-    let start = map
-        .iter()
-        .filter_map(|(Complex { re: i, im: j }, _)| if j == &0 { Some(*i) } else { None })
-        .min()
-        .unwrap();
+pub fn part1((map, instr): (Map, Path)) -> Result<Answer> {
+    let start = start(&map)?;
     let mut position = Complex::new(start, 0);
     let mut direction = Complex::new(1, 0);
     // _draw_board(&map, &position, &direction);
@@ -134,8 +148,8 @@ pub fn part1((map, instr): (Map, Path)) -> isize {
                     // wrap around
                     if direction == Complex::new(1, 0) {
                         let new_re = map
-                            .iter()
-                            .filter_map(|(p, _)| if p.im == position.im { Some(p) } else { None })
+                            .keys()
+                            .filter(|p| p.im == position.im)
                             .min_by(|x, y| x.re.cmp(&y.re))
                             .unwrap();
                         if map[new_re] {
@@ -146,8 +160,8 @@ pub fn part1((map, instr): (Map, Path)) -> isize {
                         }
                     } else if direction == Complex::new(-1, 0) {
                         let new_re = map
-                            .iter()
-                            .filter_map(|(p, _)| if p.im == position.im { Some(p) } else { None })
+                            .keys()
+                            .filter(|p| p.im == position.im)
                             .max_by(|x, y| x.re.cmp(&y.re))
                             .unwrap();
                         if map[new_re] {
@@ -158,8 +172,8 @@ pub fn part1((map, instr): (Map, Path)) -> isize {
                         }
                     } else if direction == Complex::new(0, 1) {
                         let new_im = map
-                            .iter()
-                            .filter_map(|(p, _)| if p.re == position.re { Some(p) } else { None })
+                            .keys()
+                            .filter(|p| p.re == position.re)
                             .min_by(|x, y| x.im.cmp(&y.im))
                             .unwrap();
                         if map[new_im] {
@@ -170,8 +184,8 @@ pub fn part1((map, instr): (Map, Path)) -> isize {
                         }
                     } else if direction == Complex::new(0, -1) {
                         let new_im = map
-                            .iter()
-                            .filter_map(|(p, _)| if p.re == position.re { Some(p) } else { None })
+                            .keys()
+                            .filter(|p| p.re == position.re)
                             .max_by(|x, y| x.im.cmp(&y.im))
                             .unwrap();
                         if map[new_im] {
@@ -190,7 +204,7 @@ pub fn part1((map, instr): (Map, Path)) -> isize {
         }
         // _draw_board(&map, &position, &direction);
     }
-    score(&position, &direction)
+    Ok(score(&position, &direction).into())
 }
 
 // I do not have a general solution for all folds possible, I hardcode the one for my
@@ -212,15 +226,12 @@ fn get_side(c: Complex<isize>, square_size: isize) -> Option<u8> {
     }
 }
 
-pub fn part2((map, instr): (Map, Path)) -> isize {
-    let start = map
-        .iter()
-        .filter_map(|(Complex { re: i, im: j }, _)| if j == &0 { Some(*i) } else { None })
-        .min()
-        .unwrap();
+pub fn part2((map, instr): (Map, Path)) -> Result<Answer> {
+    let start = start(&map)?;
     let mut position = Complex::new(start, 0);
     let mut direction = Complex::new(1, 0);
-    let square_size = (map.keys().map(|p| p.re).max().unwrap() + 1) / 3;
+    let unsupported = || Error::no_solution(DAY, "the map is not folded like my input");
+    let square_size = (map.keys().map(|p| p.re).max().ok_or_else(unsupported)? + 1) / 3;
     for (i, r) in instr {
         for _ in 0..i {
             match map.get(&(position + direction)) {
@@ -234,7 +245,7 @@ pub fn part2((map, instr): (Map, Path)) -> isize {
                             // 1< => 4>
                             let new_pos =
                                 Complex::new(0, 3 * square_size - (position.im % square_size) - 1);
-                            if map[&new_pos] {
+                            if *map.get(&new_pos).ok_or_else(unsupported)? {
                                 break;
                             } else {
                                 position = new_pos;
@@ -246,7 +257,7 @@ pub fn part2((map, instr): (Map, Path)) -> isize {
                             // 1^ => 6>
                             let new_pos =
                                 Complex::new(0, 3 * square_size + (position.im % square_size));
-                            if map[&new_pos] {
+                            if *map.get(&new_pos).ok_or_else(unsupported)? {
                                 // cannot wrap as there is a wall
                                 break;
                             } else {
@@ -259,7 +270,7 @@ pub fn part2((map, instr): (Map, Path)) -> isize {
                             // 2^ => 6^
                             let new_pos =
                                 Complex::new(position.re % square_size, 4 * square_size - 1);
-                            if map[&new_pos] {
+                            if *map.get(&new_pos).ok_or_else(unsupported)? {
                                 break;
                             } else {
                                 position = new_pos;
@@ -273,7 +284,7 @@ pub fn part2((map, instr): (Map, Path)) -> isize {
                                 2 * square_size - 1,
                                 3 * square_size - (position.im % square_size) - 1,
                             );
-                            if map[&new_pos] {
+                            if *map.get(&new_pos).ok_or_else(unsupported)? {
                                 break;
                             } else {
                                 position = new_pos;
@@ -287,7 +298,7 @@ pub fn part2((map, instr): (Map, Path)) -> isize {
                                 2 * square_size - 1,
                                 square_size + (position.im % square_size),
                             );
-                            if map[&new_pos] {
+                            if *map.get(&new_pos).ok_or_else(unsupported)? {
                                 break;
                             } else {
                                 position = new_pos;
@@ -298,7 +309,7 @@ pub fn part2((map, instr): (Map, Path)) -> isize {
                         (Some(3), Complex { re: -1, im: 0 }) => {
                             // 3< => 4v
                             let new_pos = Complex::new(position.im % square_size, 2 * square_size);
-                            if map[&new_pos] {
+                            if *map.get(&new_pos).ok_or_else(unsupported)? {
                                 break;
                             } else {
                                 position = new_pos;
@@ -312,7 +323,7 @@ pub fn part2((map, instr): (Map, Path)) -> isize {
                                 2 * square_size + (position.im % square_size),
                                 square_size - 1,
                             );
-                            if map[&new_pos] {
+                            if *map.get(&new_pos).ok_or_else(unsupported)? {
                                 break;
                             } else {
                                 position = new_pos;
@@ -326,7 +337,7 @@ pub fn part2((map, instr): (Map, Path)) -> isize {
                                 square_size,
                                 square_size + (position.re % square_size),
                             );
-                            if map[&new_pos] {
+                            if *map.get(&new_pos).ok_or_else(unsupported)? {
                                 break;
                             } else {
                                 position = new_pos;
@@ -340,7 +351,7 @@ pub fn part2((map, instr): (Map, Path)) -> isize {
                                 square_size,
                                 square_size - (position.im % square_size) - 1,
                             );
-                            if map[&new_pos] {
+                            if *map.get(&new_pos).ok_or_else(unsupported)? {
                                 break;
                             } else {
                                 position = new_pos;
@@ -354,7 +365,7 @@ pub fn part2((map, instr): (Map, Path)) -> isize {
                                 3 * square_size - 1,
                                 square_size - (position.im % square_size) - 1,
                             );
-                            if map[&new_pos] {
+                            if *map.get(&new_pos).ok_or_else(unsupported)? {
                                 break;
                             } else {
                                 position = new_pos;
@@ -368,7 +379,7 @@ pub fn part2((map, instr): (Map, Path)) -> isize {
                                 square_size - 1,
                                 3 * square_size + (position.re % square_size),
                             );
-                            if map[&new_pos] {
+                            if *map.get(&new_pos).ok_or_else(unsupported)? {
                                 break;
                             } else {
                                 position = new_pos;
@@ -380,7 +391,7 @@ pub fn part2((map, instr): (Map, Path)) -> isize {
                             // 6< => 1v
                             let new_pos =
                                 Complex::new(square_size + (position.im % square_size), 0);
-                            if map[&new_pos] {
+                            if *map.get(&new_pos).ok_or_else(unsupported)? {
                                 break;
                             } else {
                                 position = new_pos;
@@ -394,7 +405,7 @@ pub fn part2((map, instr): (Map, Path)) -> isize {
                                 square_size + (position.im % square_size),
                                 3 * square_size - 1,
                             );
-                            if map[&new_pos] {
+                            if *map.get(&new_pos).ok_or_else(unsupported)? {
                                 break;
                             } else {
                                 position = new_pos;
@@ -406,7 +417,7 @@ pub fn part2((map, instr): (Map, Path)) -> isize {
                             // 6v => 2v
                             let new_pos =
                                 Complex::new(2 * square_size + (position.re % square_size), 0);
-                            if map[&new_pos] {
+                            if *map.get(&new_pos).ok_or_else(unsupported)? {
                                 break;
                             } else {
                                 position = new_pos;
@@ -414,12 +425,7 @@ pub fn part2((map, instr): (Map, Path)) -> isize {
                                 assert_eq!(get_side(position, square_size), Some(2))
                             }
                         }
-                        (x, y) => {
-                            println!("position {:?}", position);
-                            println!("square_size = {square_size}");
-                            println!("{:?} {:?}", x, y);
-                            panic!()
-                        }
+                        _ => return Err(unsupported()),
                     }
                 }
             }
@@ -428,5 +434,5 @@ pub fn part2((map, instr): (Map, Path)) -> isize {
             direction *= d;
         }
     }
-    score(&position, &direction)
+    Ok(score(&position, &direction).into())
 }
@@ -1,13 +1,18 @@
 use std::collections::{HashSet, VecDeque};
 
+use crate::answer::Answer;
+use crate::error::{parse_lines, Error, Result};
+use anyhow::anyhow;
 use num::Complex;
 
+const DAY: u8 = 23;
+
 type Pos = Complex<isize>;
 type Elves = HashSet<Pos>;
 type VecElves = Vec<Pos>;
 type Neighbors = (Pos, Pos, Pos);
 
-pub fn parse_input(input: &str) -> Elves {
+pub fn parse_input(input: &str) -> Result<Elves> {
     // use indoc::indoc;
     // let input = indoc! { "..............
     // ..............
@@ -22,16 +27,21 @@ pub fn parse_input(input: &str) -> Elves {
     // ..............
     // ..............
     // "};
-    input
-        .lines()
-        .enumerate()
-        .flat_map(|(j, l)| {
-            l.chars().enumerate().filter_map(move |(i, c)| match c {
-                '#' => Some(Complex::new(i as isize, j as isize)),
-                _ => None,
+    let rows = parse_lines(DAY, input, |l| {
+        l.chars()
+            .enumerate()
+            .filter_map(|(i, c)| match c {
+                '#' => Some(Ok(i as isize)),
+                '.' => None,
+                c => Some(Err(anyhow!("unknown tile '{}'", c))),
             })
-        })
-        .collect()
+            .collect::<anyhow::Result<Vec<_>>>()
+    })?;
+    Ok(rows
+        .into_iter()
+        .enumerate()
+        .flat_map(|(j, row)| row.into_iter().map(move |i| Complex::new(i, j as isize)))
+        .collect())
 }
 
 fn has_neighbor(e: &Pos, elves: &Elves) -> bool {
@@ -92,15 +102,16 @@ fn round(elves: &Elves, neighbors: &mut VecDeque<Neighbors>) -> Elves {
         .collect()
 }
 
-fn score(elves: &Elves) -> isize {
-    let x_min = elves.iter().min_by(|x, y| x.re.cmp(&y.re)).unwrap().re;
-    let x_max = elves.iter().max_by(|x, y| x.re.cmp(&y.re)).unwrap().re;
-    let y_min = elves.iter().min_by(|x, y| x.im.cmp(&y.im)).unwrap().im;
-    let y_max = elves.iter().max_by(|x, y| x.im.cmp(&y.im)).unwrap().im;
-    (x_max + 1 - x_min) * (y_max + 1 - y_min) - elves.len() as isize
+fn score(elves: &Elves) -> Result<isize> {
+    let no_elves = || Error::input(DAY, "no elves");
+    let x_min = elves.iter().map(|e| e.re).min().ok_or_else(no_elves)?;
+    let x_max = elves.iter().map(|e| e.re).max().ok_or_else(no_elves)?;
+    let y_min = elves.iter().map(|e| e.im).min().ok_or_else(no_elves)?;
+    let y_max = elves.iter().map(|e| e.im).max().ok_or_else(no_elves)?;
+    Ok((x_max + 1 - x_min) * (y_max + 1 - y_min) - elves.len() as isize)
 }
 
-pub fn part1(elves: Elves) -> isize {
+pub fn part1(elves: Elves) -> Result<Answer> {
     let mut neighbors: VecDeque<Neighbors> = VecDeque::from([
         (
             Complex::new(-1, -1),
@@ -124,10 +135,10 @@ pub fn part1(elves: Elves) -> isize {
         }
         elves = new_elves;
     }
-    score(&elves)
+    score(&elves).map(Answer::from)
 }
 
-pub fn part2(elves: Elves) -> isize {
+pub fn part2(elves: Elves) -> Result<Answer> {
     let mut neighbors: VecDeque<Neighbors> = VecDeque::from([
         (
             Complex::new(-1, -1),
@@ -149,7 +160,7 @@ pub fn part2(elves: Elves) -> isize {
         let new_elves = round(&elves, &mut neighbors);
         if elves == new_elves {
             // none moved, the process is over
-            return i;
+            return Ok(i.into());
         }
         elves = new_elves;
         i += 1;
@@ -1,9 +1,12 @@
 use std::collections::HashMap;
 
-use anyhow::{Context, Result};
+use crate::answer::Answer;
+use crate::error::{Error, Result};
 use num::{integer::lcm, Complex};
 use pathfinding::directed::dijkstra::dijkstra;
 
+const DAY: u8 = 24;
+
 type Pos = Complex<isize>;
 
 pub fn parse_input(input: &str) -> Result<(HashMap<Pos, Vec<Pos>>, isize, isize)> {
@@ -39,14 +42,14 @@ pub fn parse_input(input: &str) -> Result<(HashMap<Pos, Vec<Pos>>, isize, isize)
             })
         })
         .collect();
-    let dim_y: isize = input.lines().count().try_into()?;
-    let dim_x: isize = input
-        .lines()
-        .next()
-        .context("no lines")?
-        .chars()
-        .count()
-        .try_into()?;
+    let dim_y = input.lines().count() as isize;
+    let dim_x = input.lines().next().map_or(0, |l| l.chars().count()) as isize;
+    if dim_x < 3 || dim_y < 3 {
+        return Err(Error::input(
+            DAY,
+            "the valley needs walls around at least one tile",
+        ));
+    }
     Ok((hm, dim_x, dim_y))
 }
 
@@ -132,7 +135,7 @@ fn _draw_grid(map: &HashMap<Pos, Vec<Pos>>, dim_x: isize, dim_y: isize) {
 fn possible_next_steps(
     current: &Pos,
     minute: isize,
-    maps: &Vec<HashMap<Pos, Vec<Pos>>>,
+    maps: &[HashMap<Pos, Vec<Pos>>],
     dim_x: isize,
     dim_y: isize,
 ) -> Vec<(isize, Pos)> {
@@ -167,7 +170,7 @@ fn possible_next_steps(
         .collect()
 }
 
-pub fn part1((mut map, dim_x, dim_y): (HashMap<Pos, Vec<Pos>>, isize, isize)) -> usize {
+pub fn part1((mut map, dim_x, dim_y): (HashMap<Pos, Vec<Pos>>, isize, isize)) -> Result<Answer> {
     let mut maps = vec![];
     // compute all possible states
     for _ in 0..lcm(dim_x - 2, dim_y - 2) {
@@ -191,13 +194,12 @@ pub fn part1((mut map, dim_x, dim_y): (HashMap<Pos, Vec<Pos>>, isize, isize)) ->
         |p| p.1 == end,
     );
 
-    if let Some((_, l)) = result {
-        return l;
-    }
-    unreachable!()
+    result
+        .map(|(_, l)| l.into())
+        .ok_or_else(|| Error::no_solution(DAY, "no path to the exit"))
 }
 
-pub fn part2((mut map, dim_x, dim_y): (HashMap<Pos, Vec<Pos>>, isize, isize)) -> usize {
+pub fn part2((mut map, dim_x, dim_y): (HashMap<Pos, Vec<Pos>>, isize, isize)) -> Result<Answer> {
     let mut maps = vec![];
     // compute all possible states
     for _ in 0..lcm(dim_x - 2, dim_y - 2) {
@@ -253,9 +255,9 @@ pub fn part2((mut map, dim_x, dim_y): (HashMap<Pos, Vec<Pos>>, isize, isize)) ->
                 |p| p.1 == end,
             );
             if let Some((_, l3)) = r3 {
-                return l1 + l2 + l3;
+                return Ok((l1 + l2 + l3).into());
             }
         }
     }
-    unreachable!()
+    Err(Error::no_solution(DAY, "no path to the exit and back"))
 }
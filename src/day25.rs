@@ -1,22 +1,41 @@
-fn snafu_to_int(s: &str) -> isize {
+use crate::answer::Answer;
+use crate::error::{parse_lines, Result};
+use anyhow::anyhow;
+
+const DAY: u8 = 25;
+
+fn snafu_to_int(s: &str) -> anyhow::Result<isize> {
     s.chars()
         .rev()
         .enumerate()
         .map(|(i, c)| {
-            5isize.pow(i as u32)
+            Ok(5isize.pow(i as u32)
                 * match c {
                     '0' => 0isize,
                     '1' => 1,
                     '2' => 2,
                     '-' => -1,
                     '=' => -2,
-                    _ => unreachable!(),
-                }
+                    c => return Err(anyhow!("'{}' is not a SNAFU digit", c)),
+                })
         })
         .sum()
 }
 
 fn int_to_snafu(val: isize) -> String {
+    if val < 0 {
+        // negating a number negates each of its digits
+        return int_to_snafu(-val)
+            .chars()
+            .map(|c| match c {
+                '1' => '-',
+                '2' => '=',
+                '-' => '1',
+                '=' => '2',
+                c => c,
+            })
+            .collect();
+    }
     let mut s = vec![];
     let n = (val as f32).log(5f32).round() as u32;
     let mut carry = 0;
@@ -47,13 +66,13 @@ fn int_to_snafu(val: isize) -> String {
     s.into_iter().rev().collect()
 }
 
-pub fn parse_input(input: &str) -> Vec<isize> {
-    input.lines().map(snafu_to_int).collect()
+pub fn parse_input(input: &str) -> Result<Vec<isize>> {
+    parse_lines(DAY, input, snafu_to_int)
 }
 
-pub fn part1(input: Vec<isize>) -> String {
+pub fn part1(input: Vec<isize>) -> Result<Answer> {
     let val = input.iter().sum();
-    int_to_snafu(val)
+    Ok(int_to_snafu(val).into())
 }
 
 #[cfg(test)]
@@ -61,9 +80,23 @@ pub mod test {
     use super::*;
 
     #[test]
-    fn test_snafu_to_int() {
-        assert_eq!(snafu_to_int("1-0---0"), 12345);
-        assert_eq!(snafu_to_int("1121-1110-1=0"), 314159265);
+    fn test_snafu_to_int() -> anyhow::Result<()> {
+        assert_eq!(snafu_to_int("1-0---0")?, 12345);
+        assert_eq!(snafu_to_int("1121-1110-1=0")?, 314159265);
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_error() {
+        assert_eq!(
+            parse_input("1=-0-2\n12a1"),
+            Err(crate::error::Error::parse(
+                DAY,
+                2,
+                "12a1",
+                "'a' is not a SNAFU digit"
+            ))
+        );
     }
 
     #[test]
@@ -82,5 +115,19 @@ pub mod test {
         assert_eq!(int_to_snafu(2022), "1=11-2".to_string());
         assert_eq!(int_to_snafu(12345), "1-0---0".to_string());
         assert_eq!(int_to_snafu(314159265), "1121-1110-1=0".to_string());
+        assert_eq!(int_to_snafu(0), "0".to_string());
+        assert_eq!(int_to_snafu(-1), "-".to_string());
+        assert_eq!(int_to_snafu(-3), "-2".to_string());
+        assert_eq!(int_to_snafu(-2022), "-2--1=".to_string());
+    }
+
+    #[test]
+    fn test_negative_sum() -> anyhow::Result<()> {
+        assert_eq!(part1(parse_input("-")?)?, "-".into());
+        assert_eq!(part1(parse_input("1=\n=")?)?, "1".into());
+        for val in -200..=200 {
+            assert_eq!(snafu_to_int(&int_to_snafu(val))?, val);
+        }
+        Ok(())
     }
 }
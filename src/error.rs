@@ -0,0 +1,140 @@
+//! The error returned by every `parse_input` and `partN`.
+use std::fmt::{self, Display};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Error {
+    /// One line of the input could not be parsed. Lines are numbered from 1.
+    Parse {
+        day: u8,
+        line: usize,
+        text: String,
+        reason: String,
+    },
+    /// The input is malformed as a whole, e.g. a missing section or marker.
+    Input { day: u8, reason: String },
+    /// The input is valid but the puzzle has no answer for it.
+    NoSolution { day: u8, reason: String },
+}
+
+pub type Result<T> = std::result::Result<T, Error>;
+
+impl Error {
+    pub fn parse(day: u8, line: usize, text: &str, reason: impl Display) -> Self {
+        Error::Parse {
+            day,
+            line,
+            text: text.to_string(),
+            reason: reason.to_string(),
+        }
+    }
+
+    pub fn input(day: u8, reason: impl Display) -> Self {
+        Error::Input {
+            day,
+            reason: reason.to_string(),
+        }
+    }
+
+    pub fn no_solution(day: u8, reason: impl Display) -> Self {
+        Error::NoSolution {
+            day,
+            reason: reason.to_string(),
+        }
+    }
+
+    pub fn day(&self) -> u8 {
+        match self {
+            Error::Parse { day, .. } | Error::Input { day, .. } | Error::NoSolution { day, .. } => {
+                *day
+            }
+        }
+    }
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Error::Parse {
+                day,
+                line,
+                text,
+                reason,
+            } => write!(f, "day {}, line {}: {} in '{}'", day, line, reason, text),
+            Error::Input { day, reason } => write!(f, "day {}: invalid input: {}", day, reason),
+            Error::NoSolution { day, reason } => write!(f, "day {}: no solution: {}", day, reason),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+/// Parse each line of `input` with `f`, reporting the first failure with its
+/// line number.
+pub fn parse_lines<T, E: Display>(
+    day: u8,
+    input: &str,
+    mut f: impl FnMut(&str) -> std::result::Result<T, E>,
+) -> Result<Vec<T>> {
+    input
+        .lines()
+        .enumerate()
+        .map(|(i, line)| f(line).map_err(|e| Error::parse(day, i + 1, line, e)))
+        .collect()
+}
+
+/// Parse each block of lines separated by an empty line with `f`. On failure,
+/// the error points to the first line of the block.
+pub fn parse_blocks<T, E: Display>(
+    day: u8,
+    input: &str,
+    mut f: impl FnMut(&str) -> std::result::Result<T, E>,
+) -> Result<Vec<T>> {
+    let mut line = 1;
+    input
+        .split("\n\n")
+        .map(|block| {
+            let start = line;
+            line += block.lines().count() + 1;
+            f(block).map_err(|e| Error::parse(day, start, block.lines().next().unwrap_or(""), e))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_lines() {
+        let parsed = parse_lines(1, "1\n2\nx\n4", |l| l.parse::<u32>());
+        assert_eq!(
+            parsed,
+            Err(Error::parse(1, 3, "x", "invalid digit found in string"))
+        );
+        assert_eq!(parse_lines(1, "1\n2", |l| l.parse::<u32>()), Ok(vec![1, 2]));
+    }
+
+    #[test]
+    fn test_parse_blocks() {
+        let parsed = parse_blocks(11, "a\nb\n\nc\n\nd\ne\n\nf", |b| {
+            if b.starts_with('d') {
+                Err("no d")
+            } else {
+                Ok(b.len())
+            }
+        });
+        assert_eq!(parsed, Err(Error::parse(11, 6, "d", "no d")));
+    }
+
+    #[test]
+    fn test_display() {
+        assert_eq!(
+            Error::parse(10, 4, "jump 3", "unknown op").to_string(),
+            "day 10, line 4: unknown op in 'jump 3'"
+        );
+        assert_eq!(
+            Error::no_solution(6, "no marker").to_string(),
+            "day 6: no solution: no marker"
+        );
+    }
+}
@@ -0,0 +1,75 @@
+//! Values laid out row by row, e.g. the tree heights of day 8 or the pixels
+//! of a [`Bitmap`](crate::answer::Bitmap).
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Grid<T> {
+    width: usize,
+    cells: Vec<T>,
+}
+
+impl<T> Grid<T> {
+    /// Panics if `cells` cannot be cut into rows of `width` cells.
+    pub fn new(width: usize, cells: Vec<T>) -> Self {
+        assert!(
+            width > 0 && cells.chunks_exact(width).remainder().is_empty(),
+            "{} cells do not fill rows of {}",
+            cells.len(),
+            width
+        );
+        Grid { width, cells }
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.cells.len() / self.width
+    }
+
+    /// The cell at column `x` and row `y`.
+    pub fn get(&self, x: usize, y: usize) -> Option<&T> {
+        if x < self.width {
+            self.cells.get(y * self.width + x)
+        } else {
+            None
+        }
+    }
+
+    pub fn cells(&self) -> &[T] {
+        &self.cells
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = &[T]> {
+        self.cells.chunks(self.width)
+    }
+
+    pub fn map<U>(&self, f: impl FnMut(&T) -> U) -> Grid<U> {
+        Grid::new(self.width, self.cells.iter().map(f).collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_grid() {
+        let grid = Grid::new(2, vec![1, 0, 0, 1, 1, 1]);
+        assert_eq!((grid.width(), grid.height()), (2, 3));
+        assert_eq!(grid.get(1, 1), Some(&1));
+        assert_eq!(grid.get(2, 0), None);
+        assert_eq!(grid.get(0, 3), None);
+        assert_eq!(grid.rows().nth(2), Some(&[1, 1][..]));
+        assert_eq!(
+            grid.map(|&v| v > 0).cells(),
+            [true, false, false, true, true, true]
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "5 cells do not fill rows of 2")]
+    fn test_uneven_rows() {
+        Grid::new(2, vec![0; 5]);
+    }
+}
@@ -0,0 +1,163 @@
+use anyhow::{anyhow, Context, Result};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+/// Where the runner looks for `dayN.txt` when nothing else is configured.
+pub const DEFAULT_INPUT_DIR: &str = "input/2022";
+
+/// Tells the runner where to find the puzzle inputs.
+///
+/// Inputs are looked up as `<dir>/dayN.txt`, unless a per-day override is
+/// registered. In offline mode, a missing file is an error and no download is
+/// ever attempted.
+#[derive(Debug, Clone)]
+pub struct InputConfig {
+    pub dir: PathBuf,
+    pub offline: bool,
+    overrides: HashMap<u8, PathBuf>,
+}
+
+impl Default for InputConfig {
+    fn default() -> Self {
+        InputConfig::new(DEFAULT_INPUT_DIR)
+    }
+}
+
+impl InputConfig {
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        InputConfig {
+            dir: dir.into(),
+            offline: false,
+            overrides: HashMap::new(),
+        }
+    }
+
+    /// Read the input of `day` from `path` instead of the cache directory.
+    pub fn set_override(&mut self, day: u8, path: impl Into<PathBuf>) {
+        self.overrides.insert(day, path.into());
+    }
+
+    /// Parse an override given as `DAY=PATH`, e.g. `16=../alice/day16.txt`.
+    pub fn parse_override(&mut self, spec: &str) -> Result<()> {
+        let (day, path) = spec
+            .split_once('=')
+            .ok_or_else(|| anyhow!("expected DAY=PATH, got '{}'", spec))?;
+        let day = day
+            .trim()
+            .parse()
+            .with_context(|| format!("invalid day in '{}'", spec))?;
+        self.set_override(day, path.trim());
+        Ok(())
+    }
+
+    pub fn path(&self, day: u8) -> PathBuf {
+        match self.overrides.get(&day) {
+            Some(path) => path.clone(),
+            None => self.dir.join(format!("day{}.txt", day)),
+        }
+    }
+
+    /// All the days in `days` whose input file cannot be found, with the path
+    /// that was tried.
+    pub fn missing(&self, days: impl IntoIterator<Item = u8>) -> Vec<(u8, PathBuf)> {
+        days.into_iter()
+            .map(|day| (day, self.path(day)))
+            .filter(|(_, path)| !path.is_file())
+            .collect()
+    }
+
+    pub fn read(&self, day: u8) -> Result<String> {
+        let path = self.path(day);
+        std::fs::read_to_string(&path)
+            .with_context(|| format!("Failed to read input file '{}'", path.display()))
+    }
+
+    /// Read the input of `day`, calling `download` first if the file is
+    /// missing. `download` must store the input at `self.path(day)`, so it is
+    /// only used for the default directory: a missing override or a missing
+    /// file in another directory is an error.
+    pub fn fetch(&self, day: u8, download: impl FnOnce(u8) -> Result<()>) -> Result<String> {
+        let path = self.path(day);
+        if !path.is_file() {
+            if self.overrides.contains_key(&day) {
+                return Err(anyhow!(
+                    "Missing input file '{}' given for day {}",
+                    path.display(),
+                    day
+                ));
+            }
+            if self.offline || self.dir != Path::new(DEFAULT_INPUT_DIR) {
+                return Err(anyhow!(
+                    "Missing input file '{}', inputs are only downloaded to '{}'",
+                    path.display(),
+                    DEFAULT_INPUT_DIR
+                ));
+            }
+            download(day)?;
+        }
+        self.read(day)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_path() {
+        let mut config = InputConfig::new("cache");
+        assert_eq!(config.path(3), PathBuf::from("cache/day3.txt"));
+        config.set_override(16, "other/valves.txt");
+        assert_eq!(config.path(16), PathBuf::from("other/valves.txt"));
+    }
+
+    #[test]
+    fn test_parse_override() -> Result<()> {
+        let mut config = InputConfig::default();
+        config.parse_override("16=../alice/day16.txt")?;
+        assert_eq!(config.path(16), PathBuf::from("../alice/day16.txt"));
+        assert!(config.parse_override("16").is_err());
+        assert!(config.parse_override("x=day16.txt").is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_missing() {
+        let mut config = InputConfig::new("does/not/exist");
+        config.set_override(1, "input/2022/day1.txt");
+        assert_eq!(
+            config.missing([1, 2]),
+            vec![(2, PathBuf::from("does/not/exist/day2.txt"))]
+        );
+    }
+
+    #[test]
+    fn test_fetch() -> Result<()> {
+        let no_download = |day| -> Result<()> { panic!("downloading day {}", day) };
+        let mut config = InputConfig::default();
+        config.set_override(1, "does/not/exist/day1.txt");
+        assert_eq!(
+            config.fetch(1, no_download).unwrap_err().to_string(),
+            "Missing input file 'does/not/exist/day1.txt' given for day 1"
+        );
+        let config = InputConfig::new("does/not/exist");
+        assert_eq!(
+            config.fetch(1, no_download).unwrap_err().to_string(),
+            "Missing input file 'does/not/exist/day1.txt', inputs are only downloaded to 'input/2022'"
+        );
+        // a missing file in the default directory is downloaded there
+        let mut downloaded = None;
+        let config = InputConfig::default();
+        let error = config
+            .fetch(26, |day| {
+                downloaded = Some(day);
+                Err(anyhow!("offline"))
+            })
+            .unwrap_err();
+        assert_eq!(
+            (downloaded, error.to_string().as_str()),
+            (Some(26), "offline")
+        );
+        Ok(())
+    }
+}
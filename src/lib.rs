@@ -1,3 +1,5 @@
+pub mod answer;
+pub mod bench;
 pub mod day01;
 pub mod day02;
 pub mod day03;
@@ -23,3 +25,10 @@ pub mod day22;
 pub mod day23;
 pub mod day24;
 pub mod day25;
+pub mod error;
+pub mod grid;
+pub mod input;
+pub mod ocr;
+pub mod registry;
+pub mod render;
+pub mod verify;
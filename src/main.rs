@@ -1,66 +1,173 @@
-use adventofcode_2022::{
-    day01, day02, day03, day04, day05, day06, day07, day08, day09, day10, day11, day12, day13,
-    day14, day15, day16, day17, day18, day19, day20, day21, day22, day23, day24, day25,
-};
-use anyhow::Result;
-use aoc_next::{aoc_main, failable_parser, parser, solution, solver, Aoc};
+use adventofcode_2022::bench::{self, Format, Report};
+use adventofcode_2022::input::InputConfig;
+use adventofcode_2022::registry::{Solution, SOLUTIONS};
+use adventofcode_2022::verify::{self, Answers};
+use anyhow::{anyhow, Context, Result};
+use aoc_next::input::get_input;
+use aoc_next::Aoc;
+use itertools::Itertools;
+use std::path::PathBuf;
+use std::time::Instant;
 
 const AOC: Aoc = Aoc {
     allow_download: true,
     year: 2022,
-    solutions: &[
-        solution! {1, failable_parser!{ day01::parse_input }, solver!{ day01::part1 }},
-        solution! {1, failable_parser!{ day01::parse_input }, solver!{ day01::part2 }},
-        solution! {2, failable_parser!{ day02::parse_input }, solver!{ day02::part1 }},
-        solution! {2, failable_parser!{ day02::parse_input }, solver!{ day02::part2 }},
-        solution! {3, parser!{ day03::parse_input }, solver!{ day03::part1 }},
-        solution! {3, parser!{ day03::parse_input_p2 }, solver!{ day03::part2 }},
-        solution! {4, failable_parser!{ day04::parse_input }, solver!{ day04::part1 }},
-        solution! {4, failable_parser!{ day04::parse_input }, solver!{ day04::part2 }},
-        solution! {5, failable_parser!{ day05::parse_input }, solver!{ day05::part1 }},
-        solution! {5, failable_parser!{ day05::parse_input }, solver!{ day05::part2 }},
-        solution! {6, parser!{ day06::parse_input }, solver!{ day06::part1 }},
-        solution! {6, parser!{ day06::parse_input }, solver!{ day06::part2 }},
-        solution! {7, failable_parser!{ day07::parse_input }, solver!{ day07::part1 }},
-        solution! {7, failable_parser!{ day07::parse_input }, solver!{ day07::part2 }},
-        solution! {8, failable_parser!{ day08::parse_input }, solver!{ day08::part1 }},
-        solution! {8, failable_parser!{ day08::parse_input }, solver!{ day08::part2 }},
-        solution! {9, failable_parser!{ day09::parse_input }, solver!{ day09::part1 }},
-        solution! {9, failable_parser!{ day09::parse_input }, solver!{ day09::part2 }},
-        solution! {10, failable_parser!{ day10::parse_input }, solver!{ day10::part1 }},
-        solution! {10, failable_parser!{ day10::parse_input }, solver!{ day10::part2 }},
-        solution! {11, failable_parser!{ day11::parse_input }, solver!{ day11::part1 }},
-        solution! {11, failable_parser!{ day11::parse_input }, solver!{ day11::part2 }},
-        solution! {12, failable_parser!{ day12::parse_input }, solver!{ day12::part1 }},
-        solution! {12, failable_parser!{ day12::parse_input }, solver!{ day12::part2 }},
-        solution! {13, failable_parser!{ day13::parse_input }, solver!{ day13::part1 }},
-        solution! {13, failable_parser!{ day13::parse_input_p2 }, solver!{ day13::part2 }},
-        solution! {14, parser!{ day14::parse_input }, solver!{ day14::part1 }},
-        solution! {14, parser!{ day14::parse_input }, solver!{ day14::part2 }},
-        solution! {15, failable_parser!{ day15::parse_input }, solver!{ day15::part1 }},
-        solution! {15, failable_parser!{ day15::parse_input }, solver!{ day15::part2 }},
-        solution! {16, parser!{ day16::parse_input }, solver!{ day16::part1 }},
-        solution! {16, parser!{ day16::parse_input }, solver!{ day16::part2 }},
-        solution! {17, failable_parser!{ day17::parse_input }, solver!{ day17::part1 }},
-        solution! {17, failable_parser!{ day17::parse_input }, solver!{ day17::part2 }},
-        solution! {18, failable_parser!{ day18::parse_input }, solver!{ day18::part1 }},
-        solution! {18, failable_parser!{ day18::parse_input }, solver!{ day18::part2 }},
-        solution! {19, failable_parser!{ day19::parse_input }, solver!{ day19::part1 }},
-        solution! {19, failable_parser!{ day19::parse_input }, solver!{ day19::part2 }},
-        solution! {20, failable_parser!{ day20::parse_input }, solver!{ day20::part1 }},
-        solution! {20, failable_parser!{ day20::parse_input }, solver!{ day20::part2 }},
-        solution! {21, failable_parser!{ day21::parse_input }, solver!{ day21::part1 }},
-        solution! {21, failable_parser!{ day21::parse_input }, solver!{ day21::part2 }},
-        solution! {22, failable_parser!{ day22::parse_input }, solver!{ day22::part1 }},
-        solution! {22, failable_parser!{ day22::parse_input }, solver!{ day22::part2 }},
-        solution! {23, parser!{ day23::parse_input }, solver!{ day23::part1 }},
-        solution! {23, parser!{ day23::parse_input }, solver!{ day23::part2 }},
-        solution! {24, failable_parser!{ day24::parse_input }, solver!{ day24::part1 }},
-        solution! {24, failable_parser!{ day24::parse_input }, solver!{ day24::part2 }},
-        solution! {25, parser!{ day25::parse_input }, solver!{ day25::part1 }},
-    ],
+    solutions: &[],
 };
 
+fn run(solution: &Solution, input: &str) {
+    print!(
+        "Day {} - {} with {}: ",
+        solution.day, solution.solver, solution.parser
+    );
+
+    let parse_start = Instant::now();
+    let parsed_input = (solution.parse)(input);
+    let parse_end = Instant::now();
+
+    match parsed_input {
+        Ok(parsed_input) => {
+            let run_start = Instant::now();
+            let result = (solution.solve)(parsed_input);
+            let run_end = Instant::now();
+
+            match result {
+                Ok(result) => {
+                    println!("{}", result);
+                    println!("\tparser: {:?}", parse_end - parse_start);
+                    println!("\tsolver: {:?}", run_end - run_start);
+                }
+                Err(e) => eprintln!("Solver failed: {}", e),
+            }
+        }
+        Err(e) => eprintln!("Parser failed: {}", e),
+    }
+}
+
+fn verify(solutions: &[&Solution], inputs: &InputConfig, answers: &Answers) -> Result<()> {
+    println!("{:>3} {:>4}  result", "day", "part");
+    let (mut failures, mut passes) = (0, 0);
+    for solution in solutions {
+        let check = verify::check(solution, answers, inputs);
+        println!("{:>3} {:>4}  {}", check.day, check.part, check.outcome);
+        if check.outcome.is_failure() {
+            failures += 1;
+        } else if check.outcome == verify::Outcome::Pass {
+            passes += 1;
+        }
+    }
+    if failures > 0 {
+        return Err(anyhow!(
+            "{} solution(s) did not give the recorded answer",
+            failures
+        ));
+    }
+    if passes == 0 {
+        return Err(anyhow!("No solution was checked against a recorded answer"));
+    }
+    Ok(())
+}
+
+fn bench(solutions: &[&Solution], inputs: &InputConfig, runs: usize, format: Format) -> Result<()> {
+    let mut timings = Vec::new();
+    for solution in solutions {
+        if !inputs.missing([solution.day]).is_empty() {
+            eprintln!("Skipping day {}: no input", solution.day);
+            continue;
+        }
+        let input = inputs.read(solution.day)?;
+        timings.push(bench::time(solution, &input, runs)?);
+    }
+    print!("{}", Report(&timings, format));
+    Ok(())
+}
+
+const USAGE: &str =
+    "usage: adventofcode_2022 [DAY] [--offline] [--input-dir DIR] [--input DAY=PATH]...
+                         [--verify [--answers FILE]] [--bench RUNS [--format text|csv|json]]";
+
+struct Args {
+    day: Option<u8>,
+    inputs: InputConfig,
+    verify: bool,
+    answers: Option<PathBuf>,
+    bench: Option<usize>,
+    format: Format,
+}
+
+fn parse_args() -> Result<Args> {
+    let mut day = None;
+    let mut inputs = InputConfig::default();
+    let mut verify = false;
+    let mut answers = None;
+    let mut bench = None;
+    let mut format = Format::Text;
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--offline" => inputs.offline = true,
+            "--input-dir" => inputs.dir = args.next().context(USAGE)?.into(),
+            "--input" => inputs.parse_override(&args.next().context(USAGE)?)?,
+            "--verify" => verify = true,
+            "--answers" => answers = Some(args.next().context(USAGE)?.into()),
+            "--bench" => bench = Some(args.next().context(USAGE)?.parse()?),
+            "--format" => format = args.next().context(USAGE)?.parse()?,
+            _ => match arg.parse() {
+                Ok(d) if day.is_none() => day = Some(d),
+                _ => return Err(anyhow!("unexpected argument '{}'\n{}", arg, USAGE)),
+            },
+        }
+    }
+    Ok(Args {
+        day,
+        inputs,
+        verify,
+        answers,
+        bench,
+        format,
+    })
+}
+
 pub fn main() -> Result<()> {
-    aoc_main(AOC)
+    let Args {
+        day,
+        inputs,
+        verify,
+        answers,
+        bench,
+        format,
+    } = parse_args()?;
+
+    let solutions = SOLUTIONS
+        .iter()
+        .filter(|s| day.is_none() || day == Some(s.day))
+        .collect_vec();
+
+    if let Some(runs) = bench {
+        return self::bench(&solutions, &inputs, runs, format);
+    }
+    println!("AOC {}", AOC.year);
+    if verify {
+        let answers = answers.unwrap_or_else(|| inputs.dir.join("answers.toml"));
+        return self::verify(&solutions, &inputs, &Answers::load(&answers)?);
+    }
+
+    let missing = inputs.missing(solutions.iter().map(|s| s.day).dedup());
+    if inputs.offline && !missing.is_empty() {
+        for (day, path) in &missing {
+            eprintln!("Missing input for day {}: '{}'", day, path.display());
+        }
+        return Err(anyhow!(
+            "{} input file(s) missing and offline mode is enabled",
+            missing.len()
+        ));
+    }
+
+    for solution in solutions {
+        // aoc-next downloads to the default directory, `fetch` reads it back
+        let input = inputs.fetch(solution.day, |day| get_input(&AOC, day).map(drop))?;
+        run(solution, &input)
+    }
+
+    Ok(())
 }
@@ -0,0 +1,112 @@
+//! Read the letters drawn on a screen in the 4x6 block font used by the
+//! puzzles, e.g. day 10 part 2.
+use crate::answer::Bitmap;
+use anyhow::{anyhow, Result};
+
+pub const GLYPH_WIDTH: usize = 4;
+pub const GLYPH_HEIGHT: usize = 6;
+/// Letters are separated by one blank column.
+const GLYPH_SPACING: usize = GLYPH_WIDTH + 1;
+
+/// Every letter known to appear in the puzzle answers.
+const FONT: &[(char, [&str; GLYPH_HEIGHT])] = &[
+    ('A', [".##.", "#..#", "#..#", "####", "#..#", "#..#"]),
+    ('B', ["###.", "#..#", "###.", "#..#", "#..#", "###."]),
+    ('C', [".##.", "#..#", "#...", "#...", "#..#", ".##."]),
+    ('E', ["####", "#...", "###.", "#...", "#...", "####"]),
+    ('F', ["####", "#...", "###.", "#...", "#...", "#..."]),
+    ('G', [".##.", "#..#", "#...", "#.##", "#..#", ".###"]),
+    ('H', ["#..#", "#..#", "####", "#..#", "#..#", "#..#"]),
+    ('I', [".###", "..#.", "..#.", "..#.", "..#.", ".###"]),
+    ('J', ["..##", "...#", "...#", "...#", "#..#", ".##."]),
+    ('K', ["#..#", "#.#.", "##..", "#.#.", "#.#.", "#..#"]),
+    ('L', ["#...", "#...", "#...", "#...", "#...", "####"]),
+    ('O', [".##.", "#..#", "#..#", "#..#", "#..#", ".##."]),
+    ('P', ["###.", "#..#", "#..#", "###.", "#...", "#..."]),
+    ('R', ["###.", "#..#", "#..#", "###.", "#.#.", "#..#"]),
+    ('S', [".###", "#...", "#...", ".##.", "...#", "###."]),
+    ('U', ["#..#", "#..#", "#..#", "#..#", "#..#", ".##."]),
+    ('Y', ["#...", "#...", ".#.#", "..#.", "..#.", "..#."]),
+    ('Z', ["####", "...#", "..#.", ".#..", "#...", "####"]),
+];
+
+fn matches(bitmap: &Bitmap, x: usize, glyph: &[&str; GLYPH_HEIGHT]) -> bool {
+    glyph.iter().enumerate().all(|(y, row)| {
+        row.chars()
+            .enumerate()
+            .all(|(dx, c)| bitmap.is_lit(x + dx, y) == (c == '#'))
+    })
+}
+
+/// Decode every letter of `bitmap`, from left to right. Fails on the first
+/// glyph that is not in the font.
+pub fn read(bitmap: &Bitmap) -> Result<String> {
+    if bitmap.height() != GLYPH_HEIGHT {
+        return Err(anyhow!(
+            "expected a screen {} pixels high, got {}",
+            GLYPH_HEIGHT,
+            bitmap.height()
+        ));
+    }
+    (0..(bitmap.width() + 1) / GLYPH_SPACING)
+        .map(|i| {
+            let x = i * GLYPH_SPACING;
+            FONT.iter()
+                .find(|(_, glyph)| matches(bitmap, x, glyph))
+                .map(|&(c, _)| c)
+                .ok_or_else(|| anyhow!("unknown letter at column {}", x))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use itertools::Itertools;
+
+    fn bitmap(rows: &[&str]) -> Bitmap {
+        let width = rows[0].len();
+        Bitmap::new(width, rows.concat().chars().map(|c| c == '#').collect())
+    }
+
+    #[test]
+    fn test_read() -> Result<()> {
+        let screen = bitmap(&[
+            "#..#.####.###..",
+            "#..#.#....#..#.",
+            "####.###..#..#.",
+            "#..#.#....###..",
+            "#..#.#....#.#..",
+            "#..#.####.#..#.",
+        ]);
+        assert_eq!(read(&screen)?, "HER");
+        Ok(())
+    }
+
+    #[test]
+    fn test_read_font() -> Result<()> {
+        // every letter of the font side by side, with a blank column between
+        let rows: Vec<String> = (0..GLYPH_HEIGHT)
+            .map(|y| FONT.iter().map(|(_, glyph)| glyph[y]).join("."))
+            .collect();
+        let rows: Vec<&str> = rows.iter().map(String::as_str).collect();
+        assert_eq!(read(&bitmap(&rows))?, "ABCEFGHIJKLOPRSUYZ");
+        Ok(())
+    }
+
+    #[test]
+    fn test_read_unknown() {
+        let screen = bitmap(&[
+            "#..#.#...",
+            "#..#.#...",
+            "####.#...",
+            "#..#.#...",
+            "#..#.#...",
+            "#..#.#...",
+        ]);
+        assert_eq!(
+            read(&screen).unwrap_err().to_string(),
+            "unknown letter at column 5"
+        );
+    }
+}
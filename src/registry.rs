@@ -0,0 +1,144 @@
+//! The list of every solution, usable without the `aoc-next` runner.
+//!
+//! ```
+//! use adventofcode_2022::registry;
+//!
+//! let solution = registry::find(1, 2).unwrap();
+//! let answer = solution.run("1\n2\n\n3\n\n4\n\n5").unwrap();
+//! assert_eq!(answer.to_string(), "12");
+//! ```
+use crate::answer::Answer;
+use crate::*;
+use anyhow::{anyhow, Result};
+use std::any::Any;
+
+/// The output of a parser, waiting to be handed to the matching solver.
+pub type Parsed = Box<dyn Any>;
+
+/// A parser and a solver for one part of one day.
+///
+/// Both are type-erased so solutions of different days can live in the same
+/// list: `parse` boxes its output, and `solve` unboxes it.
+pub struct Solution {
+    pub day: u8,
+    pub part: u8,
+    pub parser: &'static str,
+    pub solver: &'static str,
+    pub parse: fn(&str) -> Result<Parsed>,
+    pub solve: fn(Parsed) -> Result<Answer>,
+}
+
+impl Solution {
+    pub fn run(&self, input: &str) -> Result<Answer> {
+        (self.solve)((self.parse)(input)?)
+    }
+}
+
+macro_rules! solution {
+    ($day:expr, $part:expr, $parser:path, $solver:path) => {
+        Solution {
+            day: $day,
+            part: $part,
+            parser: stringify!($parser),
+            solver: stringify!($solver),
+            parse: |input| Ok(Box::new($parser(input)?)),
+            solve: |parsed| {
+                let parsed = parsed.downcast().map_err(|_| {
+                    anyhow!("{} got the output of another parser", stringify!($solver))
+                })?;
+                Ok($solver(*parsed)?)
+            },
+        }
+    };
+}
+
+pub const SOLUTIONS: &[Solution] = &[
+    solution!(1, 1, day01::parse_input, day01::part1),
+    solution!(1, 2, day01::parse_input, day01::part2),
+    solution!(2, 1, day02::parse_input, day02::part1),
+    solution!(2, 2, day02::parse_input, day02::part2),
+    solution!(3, 1, day03::parse_input, day03::part1),
+    solution!(3, 2, day03::parse_input_p2, day03::part2),
+    solution!(4, 1, day04::parse_input, day04::part1),
+    solution!(4, 2, day04::parse_input, day04::part2),
+    solution!(5, 1, day05::parse_input, day05::part1),
+    solution!(5, 2, day05::parse_input, day05::part2),
+    solution!(6, 1, day06::parse_input, day06::part1),
+    solution!(6, 2, day06::parse_input, day06::part2),
+    solution!(7, 1, day07::parse_input, day07::part1),
+    solution!(7, 2, day07::parse_input, day07::part2),
+    solution!(8, 1, day08::parse_input, day08::part1),
+    solution!(8, 2, day08::parse_input, day08::part2),
+    solution!(9, 1, day09::parse_input, day09::part1),
+    solution!(9, 2, day09::parse_input, day09::part2),
+    solution!(10, 1, day10::parse_input, day10::part1),
+    solution!(10, 2, day10::parse_input, day10::part2),
+    solution!(11, 1, day11::parse_input, day11::part1),
+    solution!(11, 2, day11::parse_input, day11::part2),
+    solution!(12, 1, day12::parse_input, day12::part1),
+    solution!(12, 2, day12::parse_input, day12::part2),
+    solution!(13, 1, day13::parse_input, day13::part1),
+    solution!(13, 2, day13::parse_input_p2, day13::part2),
+    solution!(14, 1, day14::parse_input, day14::part1),
+    solution!(14, 2, day14::parse_input, day14::part2),
+    solution!(15, 1, day15::parse_input, day15::part1),
+    solution!(15, 2, day15::parse_input, day15::part2),
+    solution!(16, 1, day16::parse_input, day16::part1),
+    solution!(16, 2, day16::parse_input, day16::part2),
+    solution!(17, 1, day17::parse_input, day17::part1),
+    solution!(17, 2, day17::parse_input, day17::part2),
+    solution!(18, 1, day18::parse_input, day18::part1),
+    solution!(18, 2, day18::parse_input, day18::part2),
+    solution!(19, 1, day19::parse_input, day19::part1),
+    solution!(19, 2, day19::parse_input, day19::part2),
+    solution!(20, 1, day20::parse_input, day20::part1),
+    solution!(20, 2, day20::parse_input, day20::part2),
+    solution!(21, 1, day21::parse_input, day21::part1),
+    solution!(21, 2, day21::parse_input, day21::part2),
+    solution!(22, 1, day22::parse_input, day22::part1),
+    solution!(22, 2, day22::parse_input, day22::part2),
+    solution!(23, 1, day23::parse_input, day23::part1),
+    solution!(23, 2, day23::parse_input, day23::part2),
+    solution!(24, 1, day24::parse_input, day24::part1),
+    solution!(24, 2, day24::parse_input, day24::part2),
+    solution!(25, 1, day25::parse_input, day25::part1),
+];
+
+/// The solution of `part` for `day`, if there is one.
+pub fn find(day: u8, part: u8) -> Option<&'static Solution> {
+    SOLUTIONS.iter().find(|s| s.day == day && s.part == part)
+}
+
+/// All the solutions for `day`, in part order.
+pub fn day(day: u8) -> impl Iterator<Item = &'static Solution> {
+    SOLUTIONS.iter().filter(move |s| s.day == day)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_find() {
+        assert_eq!(SOLUTIONS.len(), 49);
+        let solution = find(13, 2).unwrap();
+        assert_eq!(solution.parser, "day13::parse_input_p2");
+        assert_eq!(solution.solver, "day13::part2");
+        assert!(find(25, 2).is_none());
+        assert_eq!(day(3).map(|s| s.part).collect::<Vec<_>>(), vec![1, 2]);
+    }
+
+    #[test]
+    fn test_run() -> Result<()> {
+        let answer = find(6, 1).unwrap().run("mjqjpqmgbljsphdztnvjfqwrcgsmlb")?;
+        assert_eq!(answer.to_string(), "7");
+        assert!(find(4, 1).unwrap().run("not a pair").is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_solve_wrong_input() {
+        let parsed = (find(6, 1).unwrap().parse)("abcd").unwrap();
+        assert!((find(1, 1).unwrap().solve)(parsed).is_err());
+    }
+}
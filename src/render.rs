@@ -0,0 +1,230 @@
+//! Draw a [`Bitmap`] or a [`Heatmap`] as plain text, as a netpbm image or in
+//! a terminal.
+//!
+//! The netpbm formats are the plain (ASCII) variants, so the output can be
+//! written to a `.pbm`, `.pgm` or `.ppm` file and opened by most image viewers.
+use crate::answer::Bitmap;
+use crate::grid::Grid;
+use anyhow::anyhow;
+use std::fmt;
+
+/// Color of the lit pixels in PPM and ANSI output, a CRT green.
+const LIT: [u8; 3] = [51, 255, 51];
+const DARK: [u8; 3] = [0, 0, 0];
+/// Netpbm readers may reject longer lines.
+const MAX_LINE: usize = 70;
+
+/// Characters for the text heatmap, from the lowest value to the highest.
+const RAMP: &[u8] = b" .:-=+*#%@";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    /// `#` for lit pixels and `.` for dark ones, as in the puzzle text.
+    Text,
+    /// Black and white, lit pixels are black.
+    Pbm,
+    /// Grayscale, lit pixels are white.
+    Pgm,
+    Ppm,
+    /// Colored blocks for a terminal with ANSI escape codes.
+    Ansi,
+}
+
+impl std::str::FromStr for Format {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> anyhow::Result<Self> {
+        match s {
+            "text" => Ok(Format::Text),
+            "pbm" => Ok(Format::Pbm),
+            "pgm" => Ok(Format::Pgm),
+            "ppm" => Ok(Format::Ppm),
+            "ansi" => Ok(Format::Ansi),
+            _ => Err(anyhow!(
+                "unknown format '{}', use text, pbm, pgm, ppm or ansi",
+                s
+            )),
+        }
+    }
+}
+
+pub struct Render<'a>(pub &'a Bitmap, pub Format);
+
+/// Write `values` separated by spaces, starting a new line before it gets too
+/// long.
+fn write_values(f: &mut fmt::Formatter, values: impl Iterator<Item = String>) -> fmt::Result {
+    let mut len = 0;
+    for value in values {
+        if len > 0 && len + 1 + value.len() > MAX_LINE {
+            writeln!(f)?;
+            len = 0;
+        }
+        if len > 0 {
+            write!(f, " ")?;
+            len += 1;
+        }
+        write!(f, "{}", value)?;
+        len += value.len();
+    }
+    writeln!(f)
+}
+
+impl fmt::Display for Render<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let Render(bitmap, format) = self;
+        let (width, height) = (bitmap.width(), bitmap.height());
+        let pixels = bitmap.cells().iter();
+        match format {
+            Format::Text => {
+                for row in bitmap.rows() {
+                    let line: String = row.iter().map(|&p| if p { '#' } else { '.' }).collect();
+                    writeln!(f, "{}", line)?;
+                }
+                Ok(())
+            }
+            Format::Pbm => {
+                writeln!(f, "P1\n{} {}", width, height)?;
+                write_values(f, pixels.map(|&p| u8::from(p).to_string()))
+            }
+            Format::Pgm => {
+                writeln!(f, "P2\n{} {}\n255", width, height)?;
+                write_values(f, pixels.map(|&p| if p { "255" } else { "0" }.to_string()))
+            }
+            Format::Ppm => {
+                writeln!(f, "P3\n{} {}\n255", width, height)?;
+                write_values(
+                    f,
+                    pixels.map(|&p| {
+                        let [r, g, b] = if p { LIT } else { DARK };
+                        format!("{} {} {}", r, g, b)
+                    }),
+                )
+            }
+            Format::Ansi => {
+                for row in bitmap.rows() {
+                    for &p in row {
+                        let [r, g, b] = if p { LIT } else { DARK };
+                        // two spaces make a roughly square pixel
+                        write!(f, "\x1b[48;2;{};{};{}m  ", r, g, b)?;
+                    }
+                    writeln!(f, "\x1b[0m")?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+/// Draws the values of a grid as shades, from dark for 0 to lit for the
+/// largest value. PBM can only tell zero from the rest.
+pub struct Heatmap<'a>(pub &'a Grid<usize>, pub Format);
+
+/// The color between `DARK` and `LIT` for a shade out of 255.
+fn mix(shade: u8) -> [u8; 3] {
+    let mut color = [0; 3];
+    for (c, (&dark, &lit)) in color.iter_mut().zip(DARK.iter().zip(LIT.iter())) {
+        *c = (dark as usize + (lit as usize - dark as usize) * shade as usize / 255) as u8;
+    }
+    color
+}
+
+impl fmt::Display for Heatmap<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let Heatmap(grid, format) = self;
+        let values = grid.cells();
+        let max = values.iter().copied().max().unwrap_or(0).max(1) as u128;
+        let shades: Vec<u8> = values
+            .iter()
+            .map(|&v| (v as u128 * 255 / max) as u8)
+            .collect();
+        let (width, height) = (grid.width(), grid.height());
+        match format {
+            Format::Text => {
+                for row in shades.chunks(width) {
+                    let line: String = row
+                        .iter()
+                        .map(|&s| RAMP[s as usize * (RAMP.len() - 1) / 255] as char)
+                        .collect();
+                    writeln!(f, "{}", line)?;
+                }
+                Ok(())
+            }
+            Format::Pbm => {
+                writeln!(f, "P1\n{} {}", width, height)?;
+                write_values(f, values.iter().map(|&v| u8::from(v > 0).to_string()))
+            }
+            Format::Pgm => {
+                writeln!(f, "P2\n{} {}\n255", width, height)?;
+                write_values(f, shades.iter().map(|s| s.to_string()))
+            }
+            Format::Ppm => {
+                writeln!(f, "P3\n{} {}\n255", width, height)?;
+                write_values(
+                    f,
+                    shades.iter().map(|&s| {
+                        let [r, g, b] = mix(s);
+                        format!("{} {} {}", r, g, b)
+                    }),
+                )
+            }
+            Format::Ansi => {
+                for row in shades.chunks(width) {
+                    for &s in row {
+                        let [r, g, b] = mix(s);
+                        write!(f, "\x1b[48;2;{};{};{}m  ", r, g, b)?;
+                    }
+                    writeln!(f, "\x1b[0m")?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bitmap() -> Bitmap {
+        Bitmap::new(3, vec![true, false, true, false, true, false])
+    }
+
+    #[test]
+    fn test_text() {
+        assert_eq!(Render(&bitmap(), Format::Text).to_string(), "#.#\n.#.\n");
+    }
+
+    #[test]
+    fn test_netpbm() {
+        assert_eq!(
+            Render(&bitmap(), Format::Pbm).to_string(),
+            "P1\n3 2\n1 0 1 0 1 0\n"
+        );
+        assert_eq!(
+            Render(&bitmap(), Format::Pgm).to_string(),
+            "P2\n3 2\n255\n255 0 255 0 255 0\n"
+        );
+        let ppm = Render(&bitmap(), Format::Ppm).to_string();
+        assert!(ppm.starts_with("P3\n3 2\n255\n51 255 51 0 0 0 51 255 51 0 0 0"));
+        assert!(ppm.lines().all(|l| l.len() <= MAX_LINE));
+    }
+
+    #[test]
+    fn test_ansi() {
+        let ansi = Render(&bitmap(), Format::Ansi).to_string();
+        assert_eq!(ansi.lines().count(), 2);
+        assert!(ansi.starts_with("\x1b[48;2;51;255;51m  \x1b[48;2;0;0;0m  "));
+        assert!(ansi.ends_with("\x1b[0m\n"));
+    }
+
+    #[test]
+    fn test_heatmap() {
+        let grid = Grid::new(2, vec![0, 2, 4, 1]);
+        let heatmap = |format| Heatmap(&grid, format).to_string();
+        assert_eq!(heatmap(Format::Text), " =\n@:\n");
+        assert_eq!(heatmap(Format::Pbm), "P1\n2 2\n0 1 1 1\n");
+        assert_eq!(heatmap(Format::Pgm), "P2\n2 2\n255\n0 127 255 63\n");
+        assert!(heatmap(Format::Ppm).ends_with("\n0 0 0 25 127 25 51 255 51 12 63 12\n"));
+        assert!(heatmap(Format::Ansi).starts_with("\x1b[48;2;0;0;0m  \x1b[48;2;25;127;25m  "));
+    }
+}
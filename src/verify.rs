@@ -0,0 +1,210 @@
+//! Check the solutions against answers recorded in a TOML file:
+//!
+//! ```toml
+//! [day1]
+//! part1 = 68442
+//! part2 = 204837
+//!
+//! [day5]
+//! part1 = "WHTLRMZRC"
+//! ```
+use crate::input::InputConfig;
+use crate::registry::Solution;
+use anyhow::{anyhow, Context, Error, Result};
+use std::collections::HashMap;
+use std::fmt;
+use std::path::Path;
+use std::str::FromStr;
+use toml::Value;
+
+/// The expected answer of each `(day, part)`, as displayed by the solver.
+#[derive(Debug, Default, PartialEq)]
+pub struct Answers(HashMap<(u8, u8), String>);
+
+impl FromStr for Answers {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let table = match s.parse::<Value>()? {
+            Value::Table(t) => t,
+            _ => return Err(anyhow!("answers must be a table")),
+        };
+        let mut answers = HashMap::new();
+        for (day_key, parts) in table {
+            let day: u8 = day_key
+                .strip_prefix("day")
+                .and_then(|d| d.parse().ok())
+                .with_context(|| format!("expected [dayN], got [{}]", day_key))?;
+            let parts = parts
+                .as_table()
+                .with_context(|| format!("[{}] is not a table", day_key))?;
+            for (part_key, value) in parts {
+                let part: u8 = part_key
+                    .strip_prefix("part")
+                    .and_then(|p| p.parse().ok())
+                    .with_context(|| {
+                        format!("expected partN in [{}], got {}", day_key, part_key)
+                    })?;
+                let value = match value {
+                    Value::String(s) => s.clone(),
+                    Value::Integer(i) => i.to_string(),
+                    v => {
+                        return Err(anyhow!(
+                            "unsupported answer for {}.{}: {}",
+                            day_key,
+                            part_key,
+                            v
+                        ))
+                    }
+                };
+                answers.insert((day, part), value);
+            }
+        }
+        Ok(Answers(answers))
+    }
+}
+
+impl Answers {
+    pub fn load(path: &Path) -> Result<Self> {
+        std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read answers file '{}'", path.display()))?
+            .parse()
+            .with_context(|| format!("Invalid answers file '{}'", path.display()))
+    }
+
+    pub fn get(&self, day: u8, part: u8) -> Option<&str> {
+        self.0.get(&(day, part)).map(|s| s.as_str())
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub enum Outcome {
+    Pass,
+    Fail {
+        expected: String,
+        actual: String,
+    },
+    /// The solution failed to parse its input or to solve it.
+    Error(String),
+    /// No input file for this day, and no recorded answer either.
+    NoInput,
+    /// No input file for this day, but an answer is recorded.
+    MissingInput {
+        expected: String,
+    },
+    /// No recorded answer for this part.
+    NoAnswer(String),
+}
+
+impl Outcome {
+    pub fn is_failure(&self) -> bool {
+        matches!(
+            self,
+            Outcome::Fail { .. } | Outcome::Error(_) | Outcome::MissingInput { .. }
+        )
+    }
+}
+
+impl fmt::Display for Outcome {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Outcome::Pass => write!(f, "ok"),
+            Outcome::Fail { expected, actual } => {
+                write!(f, "FAIL expected {}, got {}", expected, actual)
+            }
+            Outcome::Error(e) => write!(f, "ERROR {}", e),
+            Outcome::NoInput => write!(f, "skipped, no input"),
+            Outcome::MissingInput { expected } => {
+                write!(f, "FAIL no input, expected {}", expected)
+            }
+            Outcome::NoAnswer(actual) => write!(f, "no answer recorded, got {}", actual),
+        }
+    }
+}
+
+pub struct Check {
+    pub day: u8,
+    pub part: u8,
+    pub outcome: Outcome,
+}
+
+/// Run `solution` on its input and compare the result with the recorded answer.
+pub fn check(solution: &Solution, answers: &Answers, inputs: &InputConfig) -> Check {
+    let outcome = if inputs.missing([solution.day]).is_empty() {
+        let actual = inputs
+            .read(solution.day)
+            .and_then(|input| solution.run(&input))
+            .map(|answer| answer.to_string());
+        match (actual, answers.get(solution.day, solution.part)) {
+            (Err(e), _) => Outcome::Error(e.to_string()),
+            (Ok(actual), None) => Outcome::NoAnswer(actual),
+            (Ok(actual), Some(expected)) if actual == expected => Outcome::Pass,
+            (Ok(actual), Some(expected)) => Outcome::Fail {
+                expected: expected.to_string(),
+                actual,
+            },
+        }
+    } else {
+        match answers.get(solution.day, solution.part) {
+            Some(expected) => Outcome::MissingInput {
+                expected: expected.to_string(),
+            },
+            None => Outcome::NoInput,
+        }
+    };
+    Check {
+        day: solution.day,
+        part: solution.part,
+        outcome,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::registry;
+
+    #[test]
+    fn test_parse_answers() -> Result<()> {
+        let answers: Answers =
+            "[day1]\npart1 = 68442\n\n[day5]\npart2 = \"GMPMLWNMG\"\n".parse()?;
+        assert_eq!(answers.get(1, 1), Some("68442"));
+        assert_eq!(answers.get(5, 2), Some("GMPMLWNMG"));
+        assert_eq!(answers.get(1, 2), None);
+        assert!("[one]\npart1 = 1".parse::<Answers>().is_err());
+        assert!("[day1]\npart1 = 1.5".parse::<Answers>().is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_check() -> Result<()> {
+        let solution = registry::find(1, 1).unwrap();
+        let path = std::env::temp_dir().join("aoc_2022_verify_day1.txt");
+        std::fs::write(&path, "1\n2\n\n4")?;
+        let mut inputs = InputConfig::default();
+        inputs.set_override(1, &path);
+
+        let answers: Answers = "[day1]\npart1 = 4".parse()?;
+        assert_eq!(check(solution, &answers, &inputs).outcome, Outcome::Pass);
+        let answers: Answers = "[day1]\npart1 = 3".parse()?;
+        assert!(check(solution, &answers, &inputs).outcome.is_failure());
+        let answers = Answers::default();
+        assert_eq!(
+            check(solution, &answers, &inputs).outcome,
+            Outcome::NoAnswer("4".into())
+        );
+
+        inputs.set_override(1, "does/not/exist.txt");
+        assert_eq!(check(solution, &answers, &inputs).outcome, Outcome::NoInput);
+        let answers: Answers = "[day1]\npart1 = 4".parse()?;
+        let outcome = check(solution, &answers, &inputs).outcome;
+        assert_eq!(
+            outcome,
+            Outcome::MissingInput {
+                expected: "4".into()
+            }
+        );
+        assert!(outcome.is_failure());
+        Ok(())
+    }
+}